@@ -0,0 +1,71 @@
+// Generates the registry of built-in programs from the program files in the `programs`
+// directory, with their details from the program database in the same directory. A new
+// built-in program only needs its file dropped into the directory.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+use serde_json::Value;
+use sha1::Sha1;
+
+const PROGRAMS_DIR: &str = "programs";
+const DATABASE_FILE: &str = "database.json";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", PROGRAMS_DIR);
+
+    let dir = Path::new(PROGRAMS_DIR);
+    let database: Value = serde_json::from_str(&fs::read_to_string(dir.join(DATABASE_FILE)).unwrap())
+        .expect("Program database is not valid");
+
+    // Program files have no extension, or the .ch8 extension.
+    let mut paths = fs::read_dir(dir).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.is_file())
+        .filter(|path| matches!(path.extension().and_then(|extension| extension.to_str()), None | Some("ch8")))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    let mut programs = String::new();
+    let mut names = Vec::new();
+
+    for path in paths.iter() {
+        let name = path.file_stem().and_then(|stem| stem.to_str()).unwrap();
+        let file_name = path.file_name().and_then(|file_name| file_name.to_str()).unwrap();
+        let rom = fs::read(path).unwrap();
+
+        let info = &database[Sha1::from(&rom).digest().to_string()];
+        let text = |key: &str| info[key].as_str().unwrap_or_default();
+        let number = |key: &str| info[key].as_u64();
+        let authors = info["authors"].as_array()
+            .map(|authors| authors.iter().filter_map(Value::as_str).collect::<Vec<_>>())
+            .unwrap_or_default();
+        let title = match text("title") {
+            ""    => name,
+            title => title,
+        };
+
+        writeln!(programs, "    BuiltInProgram {{").unwrap();
+        writeln!(programs, "        name: {:?},", name).unwrap();
+        writeln!(programs, "        rom: include_bytes!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/{}/{}\")),", PROGRAMS_DIR, file_name).unwrap();
+        writeln!(programs, "        title: {:?},", title).unwrap();
+        writeln!(programs, "        authors: &{:?},", authors).unwrap();
+        writeln!(programs, "        year: {:?},", number("year")).unwrap();
+        writeln!(programs, "        description: {:?},", text("description")).unwrap();
+        writeln!(programs, "        controls: {:?},", text("controls")).unwrap();
+        writeln!(programs, "        speed: {:?},", number("speed")).unwrap();
+        writeln!(programs, "    }},").unwrap();
+
+        names.push(name);
+    }
+
+    let source = format!(
+        "pub static BUILT_IN_PROGRAMS: [BuiltInProgram; {count}] = [\n{programs}];\n\n\
+         pub const BUILT_IN_PROGRAM_NAMES: [&str; {count}] = {names:?};\n",
+        count = names.len(), programs = programs, names = names,
+    );
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("built_in_programs.rs"), source).unwrap();
+}
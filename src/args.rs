@@ -1,53 +1,372 @@
-use clap::{App, Arg};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 
-use super::constants::{BUILT_IN_PROGRAMS, WINDOW_SCALE_DEFAULT};
+use std::path::PathBuf;
+use super::built_in::{BUILT_IN_PROGRAMS, BUILT_IN_PROGRAM_NAMES};
+use super::constants::{BENCH_FRAMES_DEFAULT, FRONTEND_DEFAULT, LOAD_ADDRESS_DEFAULT, RECORD_SCALE_DEFAULT, RECORD_SCALE_MAX, SCREENSHOT_SCALE_DEFAULT, TERMINAL_GLYPHS_DEFAULT};
+use super::chip8::constants::PROGRAM_ADDRESS_START;
+use super::chip8::cpu::QUIRK_NAMES;
+use super::chip8::io::screenshot::ScreenshotScale;
+use super::chip8::io::terminal::Glyphs;
+use super::chip8::platform::Platform;
+use super::chip8::io::viewport::Scaling;
+use super::chip8::speed::FastForward;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ProgramArg {
     BuiltInProgram(String),
     ProgramFile(String),
 }
 
+impl ProgramArg {
+    /// Name of a built-in program, or otherwise a file path.
+    pub fn parse(value: &str) -> Self {
+        if BUILT_IN_PROGRAM_NAMES.contains(&value) {
+            ProgramArg::BuiltInProgram(value.to_owned())
+        } else {
+            ProgramArg::ProgramFile(value.to_owned())
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum FrontendArg {
+    Sdl,
+    Terminal(Glyphs),
+}
+
+#[derive(Clone)]
 pub struct Args {
-    pub window_scale: u32,
-    pub program: ProgramArg,
+    pub frontend: FrontendArg,
+    pub window_scale: Option<u32>,
+    pub scaling: Option<Scaling>,
+    pub keypad_overlay: bool,
+    pub speed: Option<u32>,
+    pub fast_forward: Option<FastForward>,
+    /// Program to run, or otherwise the launcher is shown to pick one.
+    pub program: Option<ProgramArg>,
+    /// Address which the program is loaded at, and starts running from.
+    pub load_address: usize,
+    pub quirks: Option<Vec<String>>,
+    pub palette: Option<String>,
+    pub keymap: Option<String>,
+    pub gamepad: Option<String>,
+    pub buzzer_frequency: Option<f32>,
+    pub buzzer_volume: Option<f32>,
+    pub buzzer_waveform: Option<String>,
+    pub mute: bool,
+    pub config: Option<String>,
+    pub screenshot_scale: ScreenshotScale,
+    pub screenshot_on_exit: Option<PathBuf>,
+    pub record_gif: Option<PathBuf>,
+    pub record_y4m: Option<PathBuf>,
+    pub record_audio: Option<PathBuf>,
+    pub record_scale: u32,
 }
 
-impl Args {
+/// Subcommand to execute, with its arguments. Without a subcommand, run arguments can be
+/// given directly, e.g. `--program pong` is a shortcut for `run --program pong`.
+pub enum Command {
+    Run(Box<Args>),
+    Info { program: ProgramArg },
+    List { path: Option<String> },
+    Disasm { program: ProgramArg, output: Option<PathBuf>, load_address: usize },
+    Asm { source: PathBuf, output: PathBuf, load_address: usize },
+    Bench { program: ProgramArg, frames: u32, load_address: usize },
+}
+
+impl Command {
     pub fn parse() -> Result<Self, String> {
+        let program_arg = || Arg::with_name("program")
+            .value_name("PROGRAM")
+            .required(true)
+            .help("Name of a built-in program, or file path to a CHIP-8 program, or - to read it from \
+                   standard input.");
+        let programs_help = built_in_programs_help();
+
         let app = App::new("chip8-interpreter")
             .version("0.1.0")
             .author("Dinko Osrecki")
             .about("An interpreter (emulator) for the CHIP-8 programming language.")
-            .arg(Arg::with_name("program")
-                .long("program")
-                .short("p")
-                .value_name("PROGRAM")
-                .possible_values(&BUILT_IN_PROGRAMS)
-                .required_unless("program-file")
-                .conflicts_with("program-file")
-                .help("Name of a built-in program to load.")
+            .setting(AppSettings::SubcommandsNegateReqs)
+            .setting(AppSettings::ArgsNegateSubcommands)
+            .args(&run_args(&programs_help))
+            .subcommand(SubCommand::with_name("run")
+                .about("Run a CHIP-8 program (default).")
+                .args(&run_args(&programs_help))
+            )
+            .subcommand(SubCommand::with_name("info")
+                .about("Show size, SHA-1 hash and detected platform of a program.")
+                .arg(program_arg())
+            )
+            .subcommand(SubCommand::with_name("list")
+                .about("List built-in programs, or program files in a directory or zip archive.")
+                .arg(Arg::with_name("path")
+                    .value_name("PATH")
+                    .help("Directory or zip archive to list .ch8, .sc8 and .xo8 program files of.")
+                )
             )
-            .arg(Arg::with_name("program-file")
-                .long("program-file")
-                .short("f")
-                .value_name("PROGRAM_FILE")
-                .required_unless("program")
-                .conflicts_with("program")
-                .help("File path to the CHIP-8 program to load.")
+            .subcommand(SubCommand::with_name("disasm")
+                .about("Disassemble a program into a listing, which can be assembled again.")
+                .arg(program_arg())
+                .arg(Arg::with_name("output")
+                    .long("output")
+                    .short("o")
+                    .value_name("SOURCE_FILE")
+                    .help("File to write the listing to. Defaults to standard output.")
+                )
+                .arg(load_address_arg())
             )
-            .arg(Arg::with_name("window-scale")
-                .long("window-scale")
-                .short("s")
-                .value_name("WINDOW_SCALE")
-                .help("Number to multiply CHIP-8 original window resolution with.")
+            .subcommand(SubCommand::with_name("asm")
+                .about("Assemble a program from Cowgod-style mnemonics, as written by disasm.")
+                .arg(Arg::with_name("source")
+                    .value_name("SOURCE_FILE")
+                    .required(true)
+                    .help("File path to the assembly source.")
+                )
+                .arg(Arg::with_name("output")
+                    .long("output")
+                    .short("o")
+                    .value_name("PROGRAM_FILE")
+                    .required(true)
+                    .help("File to write the assembled program to, or - to write it to standard output.")
+                )
+                .arg(load_address_arg())
+            )
+            .subcommand(SubCommand::with_name("bench")
+                .about("Run a program without a frontend as fast as possible, and report its speed.")
+                .arg(program_arg())
+                .arg(Arg::with_name("frames")
+                    .long("frames")
+                    .value_name("FRAMES")
+                    .default_value(BENCH_FRAMES_DEFAULT)
+                    .help("Number of 60Hz frames to run.")
+                )
+                .arg(load_address_arg())
             )
             .get_matches();
 
+        let command = match app.subcommand() {
+            ("run", Some(matches))    => Command::Run(Box::new(Args::from_matches(matches)?)),
+            ("info", Some(matches))   => Command::Info { program: ProgramArg::parse(matches.value_of("program").unwrap()) },
+            ("list", Some(matches))   => Command::List { path: matches.value_of("path").map(str::to_owned) },
+            ("disasm", Some(matches)) => Command::Disasm {
+                program: ProgramArg::parse(matches.value_of("program").unwrap()),
+                output: matches.value_of("output").map(PathBuf::from),
+                load_address: parse_load_address(matches.value_of("load-address").unwrap())?,
+            },
+            ("asm", Some(matches))    => Command::Asm {
+                source: PathBuf::from(matches.value_of("source").unwrap()),
+                output: PathBuf::from(matches.value_of("output").unwrap()),
+                load_address: parse_load_address(matches.value_of("load-address").unwrap())?,
+            },
+            ("bench", Some(matches))  => Command::Bench {
+                program: ProgramArg::parse(matches.value_of("program").unwrap()),
+                frames: matches.value_of("frames").unwrap().parse::<u32>().map_err(|e| e.to_string())?,
+                load_address: parse_load_address(matches.value_of("load-address").unwrap())?,
+            },
+            _                         => Command::Run(Box::new(Args::from_matches(&app)?)),
+        };
+
+        Ok(command)
+    }
+}
+
+fn run_args<'a, 'b>(programs_help: &'b str) -> Vec<Arg<'a, 'b>> {
+    vec![
+        Arg::with_name("program")
+            .long("program")
+            .short("p")
+            .value_name("PROGRAM")
+            .possible_values(&BUILT_IN_PROGRAM_NAMES)
+            .conflicts_with("program-file")
+            .help("Name of a built-in program to load. Without a program, the launcher is shown \
+                   to pick one.")
+            .long_help(programs_help),
+        Arg::with_name("program-file")
+            .long("program-file")
+            .short("f")
+            .value_name("PROGRAM_FILE")
+            .conflicts_with("program")
+            .help("File path to the CHIP-8 program to load, or - to read it from standard input. Can be \
+                   a directory or a zip archive which holds a single .ch8, .sc8 or .xo8 file, or a file \
+                   inside a zip archive, e.g. pack.zip/pong.ch8. Octo cartridges (.gif) are compiled on load."),
+        load_address_arg(),
+        Arg::with_name("quirks")
+            .long("quirks")
+            .value_name("QUIRKS")
+            .possible_values(&QUIRK_NAMES)
+            .use_delimiter(true)
+            .help("Comma-separated list of quirks which some programs rely on. Defaults to the quirks \
+                   from the config file for the program. key-wait-on-press: Fx0A does not wait for \
+                   the key to be released."),
+        Arg::with_name("window-scale")
+            .long("window-scale")
+            .short("s")
+            .value_name("WINDOW_SCALE")
+            .help("Number to multiply CHIP-8 original window resolution with. \
+                   Defaults to the window scale from the config file for the program, or the \
+                   window size from the previous run."),
+        Arg::with_name("scaling")
+            .long("scaling")
+            .value_name("SCALING")
+            .possible_values(&["integer", "fit"])
+            .help("Scale CHIP-8 pixels by a whole number, or fit them to the window. \
+                   Defaults to the scaling from the config file, or integer."),
+        Arg::with_name("keypad-overlay")
+            .long("keypad-overlay")
+            .help("Show the CHIP-8 keypad over the screen, with keys which can be clicked or touched. \
+                   Press F4 to show or hide it at any time."),
+        Arg::with_name("speed")
+            .long("speed")
+            .value_name("INSTRUCTIONS_PER_FRAME")
+            .help("Number of instructions to execute in each 60Hz frame. Defaults to the speed \
+                   from the config file for the program, or 9."),
+        Arg::with_name("fast-forward")
+            .long("fast-forward")
+            .value_name("SPEED")
+            .help("Speed while Tab is held down: a multiple of normal speed (e.g. 4), or as fast as \
+                   possible (uncapped). Defaults to the fast-forward speed from the config file, or uncapped."),
+        Arg::with_name("frontend")
+            .long("frontend")
+            .value_name("FRONTEND")
+            .possible_values(&["sdl", "terminal"])
+            .default_value(FRONTEND_DEFAULT)
+            .help("Draw in a window (sdl), or in the terminal (terminal), e.g. over SSH."),
+        Arg::with_name("terminal-glyphs")
+            .long("terminal-glyphs")
+            .value_name("GLYPHS")
+            .possible_values(&["half-block", "braille"])
+            .default_value(TERMINAL_GLYPHS_DEFAULT)
+            .help("Characters used to draw pixels in the terminal frontend."),
+        Arg::with_name("palette")
+            .long("palette")
+            .short("c")
+            .value_name("PALETTE")
+            .help("Colour palette: name of a built-in palette (default, amber, green, lcd, octo), \
+                   name of a palette from the config file, or 2 or 4 comma-separated hex \
+                   colours (e.g. '#000000,#FFB000'). Press F2 to cycle palettes at runtime."),
+        Arg::with_name("keymap")
+            .long("keymap")
+            .short("k")
+            .value_name("KEYMAP")
+            .help("Keyboard mapping to the CHIP-8 keypad: name of a preset (qwerty, azerty, qwertz, dvorak), \
+                   or name of a keymap from the config file. Defaults to the keymap from the config file \
+                   for the program, or qwerty."),
+        Arg::with_name("gamepad")
+            .long("gamepad")
+            .value_name("GAMEPAD_MAPPING")
+            .help("Game controller mapping to the CHIP-8 keypad: name of a preset (default, or a name of \
+                   a built-in program), or name of a mapping from the config file. Defaults to the mapping \
+                   from the config file for the program, or the preset for the program, or default."),
+        Arg::with_name("buzzer-frequency")
+            .long("buzzer-frequency")
+            .value_name("HZ")
+            .help("Pitch of the buzzer. Defaults to the frequency from the config file, or 440."),
+        Arg::with_name("buzzer-volume")
+            .long("buzzer-volume")
+            .value_name("VOLUME")
+            .help("Volume of the buzzer, between 0 and 1. Defaults to the volume from the config file, or 0.25."),
+        Arg::with_name("buzzer-waveform")
+            .long("buzzer-waveform")
+            .value_name("WAVEFORM")
+            .possible_values(&["square", "sine", "triangle", "noise"])
+            .help("Waveform of the buzzer. Defaults to the waveform from the config file, or square."),
+        Arg::with_name("mute")
+            .long("mute")
+            .help("Start with the buzzer muted. Press F3 to mute or unmute it at any time."),
+        Arg::with_name("screenshot-on-exit")
+            .long("screenshot-on-exit")
+            .value_name("PNG_FILE")
+            .help("Save a screenshot to the PNG file when the program exits. \
+                   Press F12 to save a screenshot into the current directory at any time."),
+        Arg::with_name("screenshot-scale")
+            .long("screenshot-scale")
+            .value_name("SCALE")
+            .possible_values(&["native", "window"])
+            .default_value(SCREENSHOT_SCALE_DEFAULT)
+            .help("Save screenshots at CHIP-8 resolution (native), or scaled as in the window (window)."),
+        Arg::with_name("record-gif")
+            .long("record-gif")
+            .value_name("GIF_FILE")
+            .help("Record gameplay into an animated GIF file from the start until the program exits. \
+                   Press F10 to start or stop recording into the current directory at any time."),
+        Arg::with_name("record-y4m")
+            .long("record-y4m")
+            .value_name("Y4M_FILE")
+            .help("Record gameplay into a raw YUV4MPEG2 video file, which can be converted \
+                   with e.g. ffmpeg."),
+        Arg::with_name("record-audio")
+            .long("record-audio")
+            .value_name("WAV_FILE")
+            .help("Record the buzzer into a WAV file, in emulated time, from the start until the \
+                   program exits."),
+        Arg::with_name("record-scale")
+            .long("record-scale")
+            .value_name("SCALE")
+            .default_value(RECORD_SCALE_DEFAULT)
+            .help("Number to multiply CHIP-8 resolution with in recordings, from 1 to 64."),
+        Arg::with_name("config")
+            .long("config")
+            .value_name("CONFIG_FILE")
+            .help("File path to the TOML config file. Defaults to config.toml in the \
+                   chip8-interpreter-rust directory of the user's config directory, e.g. \
+                   ~/.config/chip8-interpreter-rust/config.toml."),
+    ]
+}
+
+fn load_address_arg<'a, 'b>() -> Arg<'a, 'b> {
+    Arg::with_name("load-address")
+        .long("load-address")
+        .value_name("ADDRESS")
+        .default_value(LOAD_ADDRESS_DEFAULT)
+        .help("Hex address which the program is loaded at and starts from, e.g. 0x600 for ETI-660 programs.")
+}
+
+// Hex address with an optional 0x prefix, from the end of the interpreter area to the
+// last instruction which fits into CHIP-8 memory.
+fn parse_load_address(value: &str) -> Result<usize, String> {
+    let digits = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).unwrap_or(value);
+    let address_max = PROGRAM_ADDRESS_START + Platform::Chip8.program_size_max() - 2;
+
+    usize::from_str_radix(digits, 16).ok()
+        .filter(|address| (PROGRAM_ADDRESS_START..=address_max).contains(address))
+        .ok_or_else(|| format!("Invalid load address '{}', expected a hex address from {:#X} to {:#X}.", value, PROGRAM_ADDRESS_START, address_max))
+}
+
+// Help of the built-in program argument, which lists the programs and their controls.
+fn built_in_programs_help() -> String {
+    let mut help = "Name of a built-in program to load. Without a program, the launcher is shown to pick one.\n".to_owned();
+
+    for program in BUILT_IN_PROGRAMS.iter() {
+        help += &format!("\n{:<10}{}", program.name, program.description);
+        if !program.controls.is_empty() {
+            help += &format!("\n{:<10}Controls: {}", "", program.controls);
+        }
+    }
+
+    help
+}
+
+impl Args {
+    fn from_matches(app: &ArgMatches) -> Result<Self, String> {
+
         let window_scale = app.value_of("window-scale")
-            .unwrap_or(WINDOW_SCALE_DEFAULT)
-            .parse::<u32>()
+            .map(|scale| scale.parse::<u32>())
+            .transpose()
+            .map_err(|e| e.to_string())?;
+
+        let scaling = app.value_of("scaling").map(Scaling::parse).transpose()?;
+        let keypad_overlay = app.is_present("keypad-overlay");
+        let speed = app.value_of("speed")
+            .map(|speed| speed.parse::<u32>())
+            .transpose()
             .map_err(|e| e.to_string())?;
+        let fast_forward = app.value_of("fast-forward").map(FastForward::parse).transpose()?;
+
+        let frontend = match app.value_of("frontend").unwrap() {
+            "terminal" => FrontendArg::Terminal(Glyphs::parse(app.value_of("terminal-glyphs").unwrap())?),
+            _          => FrontendArg::Sdl,
+        };
 
         let built_in_program = app.value_of("program")
             .map(|p| ProgramArg::BuiltInProgram(p.to_owned()));
@@ -55,14 +374,68 @@ impl Args {
         let program_file = app.value_of("program-file")
             .map(|p| ProgramArg::ProgramFile(p.to_owned()));
 
-        let program = built_in_program.unwrap_or_else(|| program_file.unwrap());
+        let program = built_in_program.or(program_file);
+        let load_address = parse_load_address(app.value_of("load-address").unwrap())?;
+
+        let quirks = app.values_of("quirks")
+            .map(|names| names.map(str::to_owned).collect::<Vec<_>>());
+
+        let palette = app.value_of("palette").map(str::to_owned);
+        let keymap = app.value_of("keymap").map(str::to_owned);
+        let gamepad = app.value_of("gamepad").map(str::to_owned);
+        let config = app.value_of("config").map(str::to_owned);
+
+        let buzzer_frequency = app.value_of("buzzer-frequency")
+            .map(|frequency| frequency.parse::<f32>())
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        let buzzer_volume = app.value_of("buzzer-volume")
+            .map(|volume| volume.parse::<f32>())
+            .transpose()
+            .map_err(|e| e.to_string())?;
+        let buzzer_waveform = app.value_of("buzzer-waveform").map(str::to_owned);
+        let mute = app.is_present("mute");
 
-        debug!("Parsed CLI arguments: window_scale={:?}, program={:?}", window_scale, program);
+        let screenshot_scale = ScreenshotScale::parse(app.value_of("screenshot-scale").unwrap())?;
+        let screenshot_on_exit = app.value_of("screenshot-on-exit").map(PathBuf::from);
+
+        let record_gif = app.value_of("record-gif").map(PathBuf::from);
+        let record_y4m = app.value_of("record-y4m").map(PathBuf::from);
+        let record_audio = app.value_of("record-audio").map(PathBuf::from);
+        let record_scale = app.value_of("record-scale").unwrap()
+            .parse::<u32>()
+            .map_err(|e| e.to_string())?;
+        if record_scale == 0 || record_scale > RECORD_SCALE_MAX {
+            return Err(format!("Record scale must be between 1 and {}.", RECORD_SCALE_MAX));
+        }
+
+        debug!("Parsed CLI arguments: frontend={:?}, window_scale={:?}, scaling={:?}, keypad_overlay={}, speed={:?}, fast_forward={:?}, program={:?}, load_address={:#X}, quirks={:?}, palette={:?}, keymap={:?}, gamepad={:?}, buzzer_frequency={:?}, buzzer_volume={:?}, buzzer_waveform={:?}, mute={}, config={:?}, screenshot_scale={:?}, screenshot_on_exit={:?}, record_gif={:?}, record_y4m={:?}, record_audio={:?}, record_scale={}",
+            frontend, window_scale, scaling, keypad_overlay, speed, fast_forward, program, load_address, quirks, palette, keymap, gamepad, buzzer_frequency, buzzer_volume, buzzer_waveform, mute, config, screenshot_scale, screenshot_on_exit, record_gif, record_y4m, record_audio, record_scale);
 
         Ok(Args {
+            frontend,
             window_scale,
+            scaling,
+            keypad_overlay,
+            speed,
+            fast_forward,
             program,
-
+            load_address,
+            quirks,
+            palette,
+            keymap,
+            gamepad,
+            buzzer_frequency,
+            buzzer_volume,
+            buzzer_waveform,
+            mute,
+            config,
+            screenshot_scale,
+            screenshot_on_exit,
+            record_gif,
+            record_y4m,
+            record_audio,
+            record_scale,
         })
     }
 }
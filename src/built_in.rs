@@ -0,0 +1,26 @@
+// Registry of programs built into the interpreter, generated by the build script from
+// the `programs` directory, in order of their names.
+
+/// Program built into the interpreter, with its details from the program database.
+pub struct BuiltInProgram {
+    pub name: &'static str,
+    pub rom: &'static [u8],
+    pub title: &'static str,
+    pub authors: &'static [&'static str],
+    pub year: Option<u16>,
+    pub description: &'static str,
+    /// Which keys the program uses, empty when unknown.
+    pub controls: &'static str,
+    /// Recommended number of instructions per frame.
+    pub speed: Option<u32>,
+}
+
+include!(concat!(env!("OUT_DIR"), "/built_in_programs.rs"));
+
+pub fn find(name: &str) -> Option<&'static BuiltInProgram> {
+    BUILT_IN_PROGRAMS.iter().find(|program| program.name == name)
+}
+
+#[cfg(test)]
+#[path = "./built_in_test.rs"]
+mod built_in_test;
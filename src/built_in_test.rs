@@ -0,0 +1,40 @@
+#![cfg(test)]
+use speculate::speculate;
+use std::fs;
+
+use super::*;
+
+speculate! {
+    describe "built_in_programs" {
+        test "hold each program file of the programs directory in order of names" {
+            let mut names = fs::read_dir("programs").unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| matches!(path.extension().and_then(|extension| extension.to_str()), None | Some("ch8")))
+                .map(|path| path.file_stem().unwrap().to_str().unwrap().to_owned())
+                .collect::<Vec<_>>();
+            names.sort();
+
+            assert_eq!(BUILT_IN_PROGRAM_NAMES[..], names[..]);
+            for (program, name) in BUILT_IN_PROGRAMS.iter().zip(BUILT_IN_PROGRAM_NAMES.iter()) {
+                assert_eq!(program.name, *name);
+            }
+        }
+
+        test "take details from the program database" {
+            let pong = find("pong").unwrap();
+
+            assert_eq!(pong.title, "Pong");
+            assert_eq!(pong.authors, ["Paul Vervalin"]);
+            assert_eq!(pong.year, Some(1990));
+            assert_eq!(pong.description, "Pong for two players");
+            assert!(pong.controls.contains("C/D"));
+            assert_eq!(pong.speed, None);
+        }
+    }
+
+    describe "find" {
+        test "not find unknown programs" {
+            assert!(find("unknown").is_none());
+        }
+    }
+}
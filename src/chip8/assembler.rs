@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+// Reads the mnemonics of Cowgod's CHIP-8 technical reference, as written by the
+// disassembler. Besides instructions, a line can define a label (`loop:`), and hold
+// data (`DB #F0, #90` or `DW #1234`). Numbers are hexadecimal with a `#` or `0x`
+// prefix, or decimal, and comments start with `;`.
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operand {
+    V(u16),
+    I,
+    IndirectI,
+    DT,
+    ST,
+    K,
+    F,
+    B,
+    Value(u16),
+}
+
+struct Line<'a> {
+    number: usize,
+    mnemonic: String,
+    operands: Vec<&'a str>,
+}
+
+/// Assemble the source of a program, which is loaded at the address.
+pub fn assemble(source: &str, address: usize) -> Result<Vec<u8>, String> {
+    let mut labels = HashMap::new();
+    let mut lines = Vec::new();
+    let mut next_address = address;
+
+    for (index, text) in source.lines().enumerate() {
+        let number = index + 1;
+        let mut text = text.split(';').next().unwrap_or_default().trim();
+
+        if let Some(colon) = text.find(':') {
+            let label = text[..colon].trim();
+            if !is_label(label) {
+                return Err(format!("Line {}: invalid label '{}'.", number, label));
+            }
+            if labels.insert(label.to_owned(), next_address as u16).is_some() {
+                return Err(format!("Line {}: label '{}' is already defined.", number, label));
+            }
+            text = text[colon + 1..].trim();
+        }
+
+        if text.is_empty() {
+            continue;
+        }
+
+        let (mnemonic, operands) = match text.find(char::is_whitespace) {
+            Some(space) => (&text[..space], text[space..].split(',').map(str::trim).collect()),
+            None        => (text, Vec::new()),
+        };
+        let line = Line { number, mnemonic: mnemonic.to_uppercase(), operands };
+
+        next_address += match line.mnemonic.as_str() {
+            "DB" => line.operands.len(),
+            "DW" => line.operands.len() * 2,
+            _    => 2,
+        };
+        lines.push(line);
+    }
+
+    let mut program = Vec::new();
+    for line in lines.iter() {
+        let operands = line.operands.iter()
+            .map(|operand| parse_operand(operand, &labels))
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(|e| format!("Line {}: {}", line.number, e))?;
+
+        assemble_line(&line.mnemonic, &operands, &mut program)
+            .map_err(|e| format!("Line {}: {}", line.number, e))?;
+    }
+
+    Ok(program)
+}
+
+fn assemble_line(mnemonic: &str, operands: &[Operand], program: &mut Vec<u8>) -> Result<(), String> {
+    match mnemonic {
+        "DB" => {
+            for operand in operands {
+                program.push(value(operand, 0xFF)? as u8);
+            }
+        },
+        "DW" => {
+            for operand in operands {
+                program.extend_from_slice(&value(operand, 0xFFFF)?.to_be_bytes());
+            }
+        },
+        _ => {
+            let opcode = encode(mnemonic, operands)?;
+            program.extend_from_slice(&opcode.to_be_bytes());
+        },
+    }
+
+    Ok(())
+}
+
+fn encode(mnemonic: &str, operands: &[Operand]) -> Result<u16, String> {
+    use Operand::*;
+
+    let opcode = match (mnemonic, operands) {
+        ("CLS",  [])                       => 0x00E0,
+        ("RET",  [])                       => 0x00EE,
+        ("SYS",  [nnn])                    => value(nnn, 0xFFF)?,
+        ("JP",   [V(0), nnn])              => 0xB000 | value(nnn, 0xFFF)?,
+        ("JP",   [nnn])                    => 0x1000 | value(nnn, 0xFFF)?,
+        ("CALL", [nnn])                    => 0x2000 | value(nnn, 0xFFF)?,
+        ("SE",   [V(x), V(y)])             => 0x5000 | x << 8 | y << 4,
+        ("SE",   [V(x), kk])               => 0x3000 | x << 8 | value(kk, 0xFF)?,
+        ("SNE",  [V(x), V(y)])             => 0x9000 | x << 8 | y << 4,
+        ("SNE",  [V(x), kk])               => 0x4000 | x << 8 | value(kk, 0xFF)?,
+        ("LD",   [V(x), V(y)])             => 0x8000 | x << 8 | y << 4,
+        ("LD",   [V(x), DT])               => 0xF007 | x << 8,
+        ("LD",   [V(x), K])                => 0xF00A | x << 8,
+        ("LD",   [V(x), IndirectI])        => 0xF065 | x << 8,
+        ("LD",   [V(x), kk])               => 0x6000 | x << 8 | value(kk, 0xFF)?,
+        ("LD",   [I, nnn])                 => 0xA000 | value(nnn, 0xFFF)?,
+        ("LD",   [DT, V(x)])               => 0xF015 | x << 8,
+        ("LD",   [ST, V(x)])               => 0xF018 | x << 8,
+        ("LD",   [F, V(x)])                => 0xF029 | x << 8,
+        ("LD",   [B, V(x)])                => 0xF033 | x << 8,
+        ("LD",   [IndirectI, V(x)])        => 0xF055 | x << 8,
+        ("ADD",  [V(x), V(y)])             => 0x8004 | x << 8 | y << 4,
+        ("ADD",  [V(x), kk])               => 0x7000 | x << 8 | value(kk, 0xFF)?,
+        ("ADD",  [I, V(x)])                => 0xF01E | x << 8,
+        ("OR",   [V(x), V(y)])             => 0x8001 | x << 8 | y << 4,
+        ("AND",  [V(x), V(y)])             => 0x8002 | x << 8 | y << 4,
+        ("XOR",  [V(x), V(y)])             => 0x8003 | x << 8 | y << 4,
+        ("SUB",  [V(x), V(y)])             => 0x8005 | x << 8 | y << 4,
+        ("SHR",  [V(x)])                   => 0x8006 | x << 8 | x << 4,
+        ("SHR",  [V(x), V(y)])             => 0x8006 | x << 8 | y << 4,
+        ("SUBN", [V(x), V(y)])             => 0x8007 | x << 8 | y << 4,
+        ("SHL",  [V(x)])                   => 0x800E | x << 8 | x << 4,
+        ("SHL",  [V(x), V(y)])             => 0x800E | x << 8 | y << 4,
+        ("RND",  [V(x), kk])               => 0xC000 | x << 8 | value(kk, 0xFF)?,
+        ("DRW",  [V(x), V(y), n])          => 0xD000 | x << 8 | y << 4 | value(n, 0xF)?,
+        ("SKP",  [V(x)])                   => 0xE09E | x << 8,
+        ("SKNP", [V(x)])                   => 0xE0A1 | x << 8,
+        _ => return Err(format!("unknown instruction {} with {} operand(s).", mnemonic, operands.len())),
+    };
+
+    Ok(opcode)
+}
+
+fn value(operand: &Operand, max: u16) -> Result<u16, String> {
+    match *operand {
+        Operand::Value(value) if value <= max => Ok(value),
+        Operand::Value(value)                 => Err(format!("value #{:X} is larger than #{:X}.", value, max)),
+        operand                               => Err(format!("expected a value, but got {:?}.", operand)),
+    }
+}
+
+fn parse_operand(text: &str, labels: &HashMap<String, u16>) -> Result<Operand, String> {
+    let upper = text.to_uppercase();
+
+    let operand = match upper.as_str() {
+        "I"   => Operand::I,
+        "[I]" => Operand::IndirectI,
+        "DT"  => Operand::DT,
+        "ST"  => Operand::ST,
+        "K"   => Operand::K,
+        "F"   => Operand::F,
+        "B"   => Operand::B,
+        _ if upper.len() == 2 && upper.starts_with('V') => {
+            let x = u16::from_str_radix(&upper[1..], 16)
+                .map_err(|_| format!("unknown register '{}'.", text))?;
+            Operand::V(x)
+        },
+        _ => Operand::Value(parse_value(text, labels)?),
+    };
+
+    Ok(operand)
+}
+
+fn parse_value(text: &str, labels: &HashMap<String, u16>) -> Result<u16, String> {
+    let hex = text.strip_prefix('#')
+        .or_else(|| text.strip_prefix("0x"))
+        .or_else(|| text.strip_prefix("0X"));
+
+    let value = match hex {
+        Some(hex) => u16::from_str_radix(hex, 16).ok(),
+        None      => text.parse::<u16>().ok().or_else(|| labels.get(text).copied()),
+    };
+
+    value.ok_or_else(|| format!("unknown value or label '{}'.", text))
+}
+
+fn is_label(text: &str) -> bool {
+    let mut chars = text.chars();
+
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+#[path = "./assembler_test.rs"]
+mod assembler_test;
@@ -0,0 +1,53 @@
+#![cfg(test)]
+use speculate::speculate;
+use std::env::current_dir;
+use crate::built_in::BUILT_IN_PROGRAM_NAMES;
+use crate::chip8::disassembler::disassemble;
+use crate::chip8::io::filesystem::Filesystem;
+
+use super::*;
+
+speculate! {
+    describe "assemble" {
+        test "assemble instructions, labels and data" {
+            let source = "
+                ; Draw a square forever.
+                start:  LD I, sprite
+                        DRW V0, V1, 2
+                loop:   JP loop
+                sprite: DB #F0, 0x90
+                        DW 4660
+            ";
+
+            let program = assemble(source, 0x200).unwrap();
+
+            assert_eq!(program, [0xA2, 0x06, 0xD0, 0x12, 0x12, 0x04, 0xF0, 0x90, 0x12, 0x34]);
+        }
+
+        test "read back disassembled built-in programs" {
+            let filesystem = Filesystem::new(current_dir().unwrap());
+
+            for name in BUILT_IN_PROGRAM_NAMES.iter() {
+                let program = filesystem.load_built_in_program(name).unwrap();
+                let source = disassemble(&program.rom, 0x200).join("\n");
+
+                assert_eq!(assemble(&source, 0x200).unwrap(), program.rom, "program {}", name);
+            }
+        }
+
+        test "report lines with unknown instructions" {
+            let error = assemble("CLS\nJP V1, #200", 0x200).unwrap_err();
+
+            assert!(error.starts_with("Line 2:"), "{}", error);
+        }
+
+        test "reject values which do not fit into the instruction" {
+            assert!(assemble("LD V0, #100", 0x200).is_err());
+            assert!(assemble("DRW V0, V1, 16", 0x200).is_err());
+        }
+
+        test "reject unknown labels" {
+            assert!(assemble("JP nowhere", 0x200).is_err());
+        }
+    }
+}
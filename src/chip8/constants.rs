@@ -1,9 +1,7 @@
-use sdl2::pixels::Color;
-
 pub const CHIP8_WIDTH: usize = 64;
 pub const CHIP8_HEIGHT: usize = 32;
 
-pub const WINDOW_TITLE: &str = "CHIP-8";
+// Programs are loaded into memory from this address.
+pub const PROGRAM_ADDRESS_START: usize = 0x200;
 
-pub const BG_COLOR: Color = Color::BLACK;
-pub const FG_COLOR: Color = Color::WHITE;
+pub const WINDOW_TITLE: &str = "CHIP-8";
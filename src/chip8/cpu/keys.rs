@@ -0,0 +1,115 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use crate::chip8::interface::KeyEvent;
+
+/// Number of frames for which key events are kept, including the current frame.
+pub const KEY_HISTORY_FRAMES: usize = 60;
+
+/// Key event stamped with the CPU cycle in which the processor received it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimedKeyEvent {
+    pub cycle: u64,
+    pub event: KeyEvent,
+}
+
+/// State of the CHIP-8 keypad, built from key events.
+///
+/// Programs usually check keys once per frame, so a key released soon after it was
+/// pressed is held down until the end of the next frame, which makes very short taps
+/// visible to them.
+pub struct Keys {
+    // Pressed keys, with the frame in which they were pressed.
+    pressed: BTreeMap<u8, u64>,
+    // Keys which were released, but are held down until their minimum duration passes.
+    pending_releases: BTreeSet<u8>,
+    frame: u64,
+    // Applied key events per frame, the last one is the current frame.
+    history: VecDeque<Vec<TimedKeyEvent>>,
+}
+
+impl Keys {
+    pub fn new() -> Self {
+        let mut history = VecDeque::with_capacity(KEY_HISTORY_FRAMES);
+        history.push_back(Vec::new());
+
+        Keys {
+            pressed: BTreeMap::new(),
+            pending_releases: BTreeSet::new(),
+            frame: 0,
+            history,
+        }
+    }
+
+    /// Apply the key event, unless it does not change the state of the key or it is a
+    /// release which is delayed. Returns the applied event.
+    pub fn handle(&mut self, event: KeyEvent, cycle: u64) -> Option<KeyEvent> {
+        let KeyEvent { keycode, is_pressed } = event;
+
+        if is_pressed {
+            self.pending_releases.remove(&keycode);
+            if self.pressed.contains_key(&keycode) {
+                return None;
+            }
+
+            self.pressed.insert(keycode, self.frame);
+        } else {
+            let pressed_frame = *self.pressed.get(&keycode)?;
+            if !self.can_release(pressed_frame) {
+                self.pending_releases.insert(keycode);
+                return None;
+            }
+
+            self.pressed.remove(&keycode);
+        }
+
+        self.record(event, cycle);
+        Some(event)
+    }
+
+    /// Move on to the next frame, and apply delayed releases which are due. Returns the
+    /// applied events.
+    pub fn end_frame(&mut self, cycle: u64) -> Vec<KeyEvent> {
+        self.frame += 1;
+        if self.history.len() == KEY_HISTORY_FRAMES {
+            self.history.pop_front();
+        }
+        self.history.push_back(Vec::new());
+
+        let due: Vec<u8> = self.pending_releases.iter()
+            .copied()
+            .filter(|keycode| self.can_release(self.pressed[keycode]))
+            .collect();
+
+        due.into_iter()
+            .map(|keycode| {
+                self.pending_releases.remove(&keycode);
+                self.pressed.remove(&keycode);
+
+                let event = KeyEvent::released(keycode);
+                self.record(event, cycle);
+                event
+            })
+            .collect()
+    }
+
+    pub fn pressed_keycodes(&self) -> BTreeSet<u8> {
+        self.pressed.keys().copied().collect()
+    }
+
+    pub fn history(&self) -> &VecDeque<Vec<TimedKeyEvent>> {
+        &self.history
+    }
+
+    fn can_release(&self, pressed_frame: u64) -> bool {
+        self.frame > pressed_frame + 1
+    }
+
+    fn record(&mut self, event: KeyEvent, cycle: u64) {
+        if let Some(frame_events) = self.history.back_mut() {
+            frame_events.push(TimedKeyEvent { cycle, event });
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./keys_test.rs"]
+mod keys_test;
@@ -0,0 +1,68 @@
+#![cfg(test)]
+use speculate::speculate;
+use maplit::btreeset;
+
+use super::*;
+
+speculate! {
+    describe "handle" {
+        test "apply presses and releases of keys" {
+            let mut keys = Keys::new();
+
+            keys.handle(KeyEvent::pressed(0x1), 0);
+            keys.end_frame(9);
+            keys.end_frame(18);
+            keys.handle(KeyEvent::pressed(0x2), 20);
+            keys.handle(KeyEvent::released(0x1), 21);
+
+            assert_eq!(keys.pressed_keycodes(), btreeset!{ 0x2 });
+        }
+
+        test "ignore events which do not change the state of a key" {
+            let mut keys = Keys::new();
+
+            assert_eq!(keys.handle(KeyEvent::released(0x1), 0), None);
+            assert_eq!(keys.handle(KeyEvent::pressed(0x1), 1), Some(KeyEvent::pressed(0x1)));
+            assert_eq!(keys.handle(KeyEvent::pressed(0x1), 2), None);
+        }
+
+        test "hold a tapped key down until the end of the next frame" {
+            let mut keys = Keys::new();
+
+            keys.handle(KeyEvent::pressed(0x1), 3);
+            assert_eq!(keys.handle(KeyEvent::released(0x1), 3), None);
+
+            assert_eq!(keys.end_frame(9), []);
+            assert_eq!(keys.pressed_keycodes(), btreeset!{ 0x1 });
+
+            assert_eq!(keys.end_frame(18), [KeyEvent::released(0x1)]);
+            assert_eq!(keys.pressed_keycodes(), btreeset!{});
+        }
+    }
+
+    describe "history" {
+        test "keep events with their cycles per frame" {
+            let mut keys = Keys::new();
+
+            keys.handle(KeyEvent::pressed(0x1), 3);
+            keys.end_frame(9);
+            keys.handle(KeyEvent::pressed(0x2), 10);
+
+            let history: Vec<_> = keys.history().iter().cloned().collect();
+            assert_eq!(history, [
+                vec![TimedKeyEvent { cycle: 3, event: KeyEvent::pressed(0x1) }],
+                vec![TimedKeyEvent { cycle: 10, event: KeyEvent::pressed(0x2) }],
+            ]);
+        }
+
+        test "keep a limited number of frames" {
+            let mut keys = Keys::new();
+
+            for frame in 0..(KEY_HISTORY_FRAMES as u64 * 2) {
+                keys.end_frame(frame * 9);
+            }
+
+            assert_eq!(keys.history().len(), KEY_HISTORY_FRAMES);
+        }
+    }
+}
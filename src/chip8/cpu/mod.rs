@@ -1,6 +1,9 @@
 mod canvas;
 mod font;
+mod keys;
 mod pc;
 mod processor;
+mod quirks;
 
 pub use processor::Processor;
+pub use quirks::{Quirks, QUIRK_NAMES};
@@ -1,19 +1,22 @@
 #![allow(non_snake_case)]
 
 use std::collections::BTreeSet;
+use std::mem;
 use super::font::FONT;
 use super::canvas::Canvas;
+use super::keys::{Keys, TimedKeyEvent};
 use super::pc::ProgramCounter;
-use crate::chip8::interface::{Input, Output};
+use super::quirks::Quirks;
+use crate::chip8::constants::PROGRAM_ADDRESS_START;
+use crate::chip8::interface::{Input, KeyEvent, Output};
 
 const RAM_BYTES: usize = 4096;
 const V_SIZE: usize = 16;
 const STACK_SIZE: usize = 16;
-const PROGRAM_ADDRESS_START: usize = 0x200;
 
 pub struct Processor {
     /// 4096B of RAM. The first 512B are reserved for the interpreter (0x000 to 0x1FF).
-    /// Programs start at 0x200.
+    /// Programs start at 0x200, unless they are loaded at another address.
     memory: [u8; RAM_BYTES],
 
     /// 16 general purpose 8b registers.
@@ -45,6 +48,21 @@ pub struct Processor {
     waiting_for_keypad: bool,
     // Store keycode into this register after waiting for keypad press.
     keycode_register: usize,
+    // Key pressed while waiting for keypad, which is stored once it is released.
+    waiting_keycode: Option<u8>,
+
+    // State of the keypad, and key events of recent frames.
+    keys: Keys,
+    // Number of executed instructions, which timestamps key events.
+    cycles: u64,
+
+    quirks: Quirks,
+
+    // Address which the program is loaded at, and starts running from.
+    load_address: usize,
+
+    // Loaded program, which is loaded again on reset.
+    program: Vec<u8>,
 
     // Read and write the state of each pixel (on/off)
     canvas: Canvas,
@@ -63,6 +81,12 @@ impl Processor {
             sound_timer: 0,
             waiting_for_keypad: false,
             keycode_register: 0,
+            waiting_keycode: None,
+            keys: Keys::new(),
+            cycles: 0,
+            quirks: Quirks::default(),
+            load_address: PROGRAM_ADDRESS_START,
+            program: Vec::new(),
             canvas: Canvas::new(),
         };
 
@@ -70,21 +94,59 @@ impl Processor {
         cpu
     }
 
+    pub fn with_quirks(mut self, quirks: Quirks) -> Self {
+        self.quirks = quirks;
+        self
+    }
+
+    /// Load programs at another address than 0x200, e.g. 0x600 for ETI-660 programs.
+    pub fn with_load_address(mut self, address: usize) -> Self {
+        self.load_address = address;
+        self.pc = ProgramCounter::new(address);
+        self
+    }
+
+    pub fn load_address(&self) -> usize {
+        self.load_address
+    }
+
     pub fn load_program(&mut self, data: &[u8]) {
-        self.load(PROGRAM_ADDRESS_START, data);
+        self.program = data.to_vec();
+        self.load(self.load_address, data);
+    }
+
+    /// Start the loaded program over, as if the machine was switched off and on again.
+    /// Keys which are held down stay pressed.
+    pub fn reset(&mut self) {
+        let program = mem::take(&mut self.program);
+        let pressed_keycodes = self.keys.pressed_keycodes();
+        *self = Processor::new().with_quirks(self.quirks).with_load_address(self.load_address);
+        self.load_program(&program);
+
+        for keycode in pressed_keycodes {
+            self.keys.handle(KeyEvent::pressed(keycode), 0);
+        }
     }
 
     pub fn execute_instruction(&mut self, input: Input) -> Output {
-        let pressed_keycodes = input.pressed_keycodes;
         self.canvas.reset_pixels_changed();
 
+        for &event in input.key_events.iter() {
+            if let Some(event) = self.keys.handle(event, self.cycles) {
+                self.handle_key_event(event);
+            }
+        }
+
+        let pressed_keycodes = self.keys.pressed_keycodes();
         if self.waiting_for_keypad {
-            self.wait_for_keypad(pressed_keycodes);
+            self.wait_for_keypad(&pressed_keycodes);
         } else {
             let opcode = self.get_opcode();
-            self.execute_opcode(opcode, pressed_keycodes);
+            self.execute_opcode(opcode, &pressed_keycodes);
         }
 
+        self.cycles += 1;
+
         Output {
             is_sound_on: self.sound_timer > 0,
             pixels: self.canvas.pixels,
@@ -92,13 +154,27 @@ impl Processor {
         }
     }
 
+    /// Called at the end of each 60Hz frame.
     pub fn update_timers(&mut self) {
+        for event in self.keys.end_frame(self.cycles) {
+            self.handle_key_event(event);
+        }
+
         if !self.waiting_for_keypad {
             self.delay_timer = self.delay_timer.saturating_sub(1);
             self.sound_timer = self.sound_timer.saturating_sub(1);
         }
     }
     
+    pub fn pressed_keycodes(&self) -> BTreeSet<u8> {
+        self.keys.pressed_keycodes()
+    }
+
+    /// Key events applied in recent frames, the last one is the current frame.
+    pub fn key_history(&self) -> impl Iterator<Item = &[TimedKeyEvent]> {
+        self.keys.history().iter().map(Vec::as_slice)
+    }
+
     fn load(&mut self, address: usize, data: &[u8]) {
         let addresses = address..;
         let bytes = data.iter();
@@ -113,10 +189,31 @@ impl Processor {
     }
 
     fn wait_for_keypad(&mut self, pressed_keycodes: &BTreeSet<u8>) {
-        if let Some(&keycode) = pressed_keycodes.first() {
-            self.V[self.keycode_register] = keycode;
-            self.waiting_for_keypad = false;
+        if self.quirks.key_wait_on_press {
+            if let Some(&keycode) = pressed_keycodes.first() {
+                self.finish_waiting_for_keypad(keycode);
+            }
+        }
+    }
+
+    // The original COSMAC VIP waits until a key is pressed and then released, so a key
+    // which is already held down when waiting starts does not count.
+    fn handle_key_event(&mut self, event: KeyEvent) {
+        if !self.waiting_for_keypad || self.quirks.key_wait_on_press {
+            return;
         }
+
+        match self.waiting_keycode {
+            None if event.is_pressed => self.waiting_keycode = Some(event.keycode),
+            Some(keycode) if keycode == event.keycode && !event.is_pressed => self.finish_waiting_for_keypad(keycode),
+            _ => {},
+        }
+    }
+
+    fn finish_waiting_for_keypad(&mut self, keycode: u8) {
+        self.V[self.keycode_register] = keycode;
+        self.waiting_for_keypad = false;
+        self.waiting_keycode = None;
     }
 
     fn get_opcode(&self) -> u16 {
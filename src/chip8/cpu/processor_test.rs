@@ -39,6 +39,66 @@ speculate! {
 
             assert_eq!(processor.memory[0x200..=0x204], rom);
         }
+
+        test "load program from the load address, and start running it there" {
+            let rom = [0x01, 0x02, 0x03, 0xF1, 0x50];
+            let mut processor = Processor::new().with_load_address(0x600);
+
+            processor.load_program(&rom);
+
+            assert_eq!(processor.memory[0x600..=0x604], rom);
+            assert_all_elems_eq!(processor.memory[0x200..0x600], 0);
+            assert_eq!(processor.pc.get_current(), 0x600);
+        }
+    }
+
+    describe "reset" {
+        test "start the loaded program over with the same quirks" {
+            let rom = [0x01, 0x02, 0x03, 0xF1, 0x50];
+            let quirks = Quirks { key_wait_on_press: true };
+            let mut processor = Processor::new().with_quirks(quirks);
+            processor.load_program(&rom);
+            processor.memory[0x300] = 0xAB;
+            processor.V[0x3] = 0x42;
+            processor.pc.jump(0x300);
+            processor.sound_timer = 10;
+
+            processor.reset();
+
+            assert_eq!(processor.memory[..0x50], FONT[..]);
+            assert_eq!(processor.memory[0x200..=0x204], rom);
+            assert_eq!(processor.memory[0x300], 0);
+            assert_eq!(processor.V, [0; 16]);
+            assert_eq!(processor.pc.get_current(), ADDRESS_START);
+            assert_eq!(processor.sound_timer, 0);
+            assert_eq!(processor.quirks, quirks);
+        }
+
+        test "start the program over from the load address" {
+            let mut processor = Processor::new().with_load_address(0x600);
+            processor.load_program(&[0x12, 0x00]);
+            processor.pc.jump(0x300);
+
+            processor.reset();
+
+            assert_eq!(processor.memory[0x600..=0x601], [0x12, 0x00]);
+            assert_eq!(processor.pc.get_current(), 0x600);
+            assert_eq!(processor.load_address(), 0x600);
+        }
+
+        test "keep keys which are held down pressed" {
+            let mut processor = Processor::new();
+            processor.load_program(&[0x12, 0x00]);
+            processor.execute_instruction(Input { key_events: &[KeyEvent::pressed(0x5)] });
+
+            processor.reset();
+
+            assert_eq!(processor.pressed_keycodes(), btreeset!{ 0x5 });
+            processor.execute_instruction(Input { key_events: &[KeyEvent::released(0x5)] });
+            processor.update_timers();
+            processor.update_timers();
+            assert_eq!(processor.pressed_keycodes(), btreeset!{});
+        }
     }
 
     describe "update_timers" {
@@ -77,6 +137,70 @@ speculate! {
         }
     }
 
+    describe "wait_for_keypad" {
+        test "ignore a key which is held down before waiting starts" {
+            let mut processor = Processor::new();
+            processor.execute_instruction(Input { key_events: &[KeyEvent::pressed(0x5)] });
+            processor.execute_opcode(0xF10A, &btreeset!{ 0x5 });
+
+            processor.update_timers();
+            processor.update_timers();
+            processor.execute_instruction(Input { key_events: &[KeyEvent::released(0x5)] });
+
+            assert_eq!(processor.waiting_for_keypad, true);
+        }
+
+        test "store a key once it is pressed and released" {
+            let mut processor = Processor::new();
+            processor.execute_opcode(0xF10A, &btreeset!{});
+
+            processor.execute_instruction(Input { key_events: &[KeyEvent::pressed(0x5)] });
+            processor.update_timers();
+            processor.update_timers();
+            assert_eq!(processor.waiting_for_keypad, true);
+
+            processor.execute_instruction(Input { key_events: &[KeyEvent::released(0x5)] });
+            assert_eq!(processor.waiting_for_keypad, false);
+            assert_eq!(processor.V[0x1], 0x5);
+        }
+
+        test "store a tapped key once its release is applied" {
+            let mut processor = Processor::new();
+            processor.execute_opcode(0xF10A, &btreeset!{});
+
+            processor.execute_instruction(Input { key_events: &[KeyEvent::pressed(0x5), KeyEvent::released(0x5)] });
+            processor.update_timers();
+            assert_eq!(processor.waiting_for_keypad, true);
+
+            processor.update_timers();
+            assert_eq!(processor.waiting_for_keypad, false);
+            assert_eq!(processor.V[0x1], 0x5);
+        }
+
+        test "store a held down key immediately with key-wait-on-press quirk" {
+            let quirks = Quirks { key_wait_on_press: true };
+            let mut processor = Processor::new().with_quirks(quirks);
+            processor.execute_opcode(0xF10A, &btreeset!{});
+
+            processor.execute_instruction(Input { key_events: &[KeyEvent::pressed(0x5)] });
+
+            assert_eq!(processor.waiting_for_keypad, false);
+            assert_eq!(processor.V[0x1], 0x5);
+        }
+    }
+
+    describe "key_history" {
+        test "timestamp key events with CPU cycles" {
+            let mut processor = Processor::new();
+
+            processor.execute_instruction(Input { key_events: &[] });
+            processor.execute_instruction(Input { key_events: &[KeyEvent::pressed(0x5)] });
+
+            let events: Vec<_> = processor.key_history().last().unwrap().to_vec();
+            assert_eq!(events, [TimedKeyEvent { cycle: 1, event: KeyEvent::pressed(0x5) }]);
+        }
+    }
+
     describe "execute_opcode" {
 
         const PRESSED_KEYCODE: u8 = 0x1;
@@ -0,0 +1,31 @@
+/// Names of quirks, as given on the command line and in the config file.
+pub const QUIRK_NAMES: [&str; 1] = ["key-wait-on-press"];
+
+/// Behaviours which differ between CHIP-8 interpreters, and which some programs rely on.
+/// By default the processor behaves like the original COSMAC VIP interpreter.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Quirks {
+    /// `Fx0A` completes as soon as any key is held down, instead of waiting until a key
+    /// is pressed and released. A key held down while `Fx0A` executes is read again
+    /// and again.
+    pub key_wait_on_press: bool,
+}
+
+impl Quirks {
+    pub fn parse<S: AsRef<str>>(names: &[S]) -> Result<Self, String> {
+        let mut quirks = Quirks::default();
+
+        for name in names.iter() {
+            match name.as_ref() {
+                "key-wait-on-press" => quirks.key_wait_on_press = true,
+                name                => return Err(format!("Unknown quirk '{}', expected one of: {}.", name, QUIRK_NAMES.join(", "))),
+            }
+        }
+
+        Ok(quirks)
+    }
+}
+
+#[cfg(test)]
+#[path = "./quirks_test.rs"]
+mod quirks_test;
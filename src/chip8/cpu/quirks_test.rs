@@ -0,0 +1,24 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "parse" {
+        test "enable given quirks" {
+            let quirks = Quirks::parse(&["key-wait-on-press"]).unwrap();
+
+            assert_eq!(quirks.key_wait_on_press, true);
+        }
+
+        test "disable all quirks by default" {
+            let no_names: [&str; 0] = [];
+
+            assert_eq!(Quirks::parse(&no_names).unwrap(), Quirks::default());
+        }
+
+        test "reject unknown quirks" {
+            assert!(Quirks::parse(&["vf-reset"]).is_err());
+        }
+    }
+}
@@ -0,0 +1,86 @@
+// Instructions are written in the mnemonics of Cowgod's CHIP-8 technical reference,
+// which the assembler reads back.
+
+/// Mnemonic of an instruction, or `None` if the opcode is not a CHIP-8 instruction.
+pub fn disassemble_opcode(opcode: u16) -> Option<String> {
+    let nibbles = (
+        (opcode & 0xF000) >> 12,
+        (opcode & 0x0F00) >> 8,
+        (opcode & 0x00F0) >> 4,
+        (opcode & 0x000F)
+    );
+
+    let nnn = opcode & 0x0FFF;
+    let x = nibbles.1;
+    let y = nibbles.2;
+    let n = nibbles.3;
+    let kk = opcode & 0x00FF;
+
+    let instruction = match nibbles {
+        (0x0, 0x0, 0xE, 0x0) => "CLS".to_owned(),
+        (0x0, 0x0, 0xE, 0xE) => "RET".to_owned(),
+        (0x0, _  , _  , _  ) => format!("SYS #{:03X}", nnn),
+        (0x1, _  , _  , _  ) => format!("JP #{:03X}", nnn),
+        (0x2, _  , _  , _  ) => format!("CALL #{:03X}", nnn),
+        (0x3, _  , _  , _  ) => format!("SE V{:X}, #{:02X}", x, kk),
+        (0x4, _  , _  , _  ) => format!("SNE V{:X}, #{:02X}", x, kk),
+        (0x5, _  , _  , 0x0) => format!("SE V{:X}, V{:X}", x, y),
+        (0x6, _  , _  , _  ) => format!("LD V{:X}, #{:02X}", x, kk),
+        (0x7, _  , _  , _  ) => format!("ADD V{:X}, #{:02X}", x, kk),
+        (0x8, _  , _  , 0x0) => format!("LD V{:X}, V{:X}", x, y),
+        (0x8, _  , _  , 0x1) => format!("OR V{:X}, V{:X}", x, y),
+        (0x8, _  , _  , 0x2) => format!("AND V{:X}, V{:X}", x, y),
+        (0x8, _  , _  , 0x3) => format!("XOR V{:X}, V{:X}", x, y),
+        (0x8, _  , _  , 0x4) => format!("ADD V{:X}, V{:X}", x, y),
+        (0x8, _  , _  , 0x5) => format!("SUB V{:X}, V{:X}", x, y),
+        (0x8, _  , _  , 0x6) => format!("SHR V{:X}, V{:X}", x, y),
+        (0x8, _  , _  , 0x7) => format!("SUBN V{:X}, V{:X}", x, y),
+        (0x8, _  , _  , 0xE) => format!("SHL V{:X}, V{:X}", x, y),
+        (0x9, _  , _  , 0x0) => format!("SNE V{:X}, V{:X}", x, y),
+        (0xA, _  , _  , _  ) => format!("LD I, #{:03X}", nnn),
+        (0xB, _  , _  , _  ) => format!("JP V0, #{:03X}", nnn),
+        (0xC, _  , _  , _  ) => format!("RND V{:X}, #{:02X}", x, kk),
+        (0xD, _  , _  , _  ) => format!("DRW V{:X}, V{:X}, #{:X}", x, y, n),
+        (0xE, _  , 0x9, 0xE) => format!("SKP V{:X}", x),
+        (0xE, _  , 0xA, 0x1) => format!("SKNP V{:X}", x),
+        (0xF, _  , 0x0, 0x7) => format!("LD V{:X}, DT", x),
+        (0xF, _  , 0x0, 0xA) => format!("LD V{:X}, K", x),
+        (0xF, _  , 0x1, 0x5) => format!("LD DT, V{:X}", x),
+        (0xF, _  , 0x1, 0x8) => format!("LD ST, V{:X}", x),
+        (0xF, _  , 0x1, 0xE) => format!("ADD I, V{:X}", x),
+        (0xF, _  , 0x2, 0x9) => format!("LD F, V{:X}", x),
+        (0xF, _  , 0x3, 0x3) => format!("LD B, V{:X}", x),
+        (0xF, _  , 0x5, 0x5) => format!("LD [I], V{:X}", x),
+        (0xF, _  , 0x6, 0x5) => format!("LD V{:X}, [I]", x),
+        _                    => return None,
+    };
+
+    Some(instruction)
+}
+
+/// Listing of a program loaded at the address, with an instruction (or data) for every
+/// two bytes. Addresses and opcodes are in comments, so the assembler reads the listing
+/// back into the same program.
+pub fn disassemble(program: &[u8], address: usize) -> Vec<String> {
+    program.chunks(2)
+        .enumerate()
+        .map(|(index, bytes)| {
+            let address = address + index * 2;
+
+            match *bytes {
+                [high, low] => {
+                    let opcode = (high as u16) << 8 | low as u16;
+                    let instruction = disassemble_opcode(opcode)
+                        .unwrap_or_else(|| format!("DW #{:04X}", opcode));
+
+                    format!("{:<20}; {:03X}: {:04X}", instruction, address, opcode)
+                },
+                _ => format!("{:<20}; {:03X}: {:02X}", format!("DB #{:02X}", bytes[0]), address, bytes[0]),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "./disassembler_test.rs"]
+mod disassembler_test;
@@ -0,0 +1,34 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "disassemble_opcode" {
+        test "write instructions with their operands" {
+            assert_eq!(disassemble_opcode(0x00E0).unwrap(), "CLS");
+            assert_eq!(disassemble_opcode(0x1234).unwrap(), "JP #234");
+            assert_eq!(disassemble_opcode(0x6A0F).unwrap(), "LD VA, #0F");
+            assert_eq!(disassemble_opcode(0x8AB4).unwrap(), "ADD VA, VB");
+            assert_eq!(disassemble_opcode(0xD125).unwrap(), "DRW V1, V2, #5");
+            assert_eq!(disassemble_opcode(0xF355).unwrap(), "LD [I], V3");
+        }
+
+        test "not know opcodes which are not CHIP-8 instructions" {
+            assert_eq!(disassemble_opcode(0x5121), None);
+            assert_eq!(disassemble_opcode(0xFFFF), None);
+        }
+    }
+
+    describe "disassemble" {
+        test "list instructions with their addresses" {
+            let listing = disassemble(&[0x00, 0xE0, 0xFF, 0xFF, 0x12], 0x200);
+
+            assert_eq!(listing, [
+                "CLS                 ; 200: 00E0",
+                "DW #FFFF            ; 202: FFFF",
+                "DB #12              ; 204: 12",
+            ]);
+        }
+    }
+}
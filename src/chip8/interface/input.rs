@@ -1,5 +1,21 @@
-use std::collections::BTreeSet;
+/// CHIP-8 key going down or up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub keycode: u8,
+    pub is_pressed: bool,
+}
+
+impl KeyEvent {
+    pub fn pressed(keycode: u8) -> Self {
+        KeyEvent { keycode, is_pressed: true }
+    }
+
+    pub fn released(keycode: u8) -> Self {
+        KeyEvent { keycode, is_pressed: false }
+    }
+}
 
 pub struct Input<'a> {
-    pub pressed_keycodes: &'a BTreeSet<u8>,
+    /// Key events which happened since the previous instruction, in order.
+    pub key_events: &'a [KeyEvent],
 }
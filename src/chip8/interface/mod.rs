@@ -1,5 +1,5 @@
 mod input;
 mod output;
 
-pub use input::Input;
+pub use input::{Input, KeyEvent};
 pub use output::Output;
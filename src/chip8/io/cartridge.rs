@@ -0,0 +1,119 @@
+use serde::Deserialize;
+
+// Octo cartridges are GIF images which hold an Octo program and the options to run it
+// with. The payload is spread over the low 2 bits of the pixels of the first frame, 4
+// pixels per byte with the most significant bits first, and starts with its length as
+// 4 big-endian bytes. The payload itself is JSON: `{"program": ..., "options": {...}}`.
+
+/// Source of an Octo program and its options, as saved by Octo.
+#[derive(Debug, Deserialize)]
+pub struct Cartridge {
+    pub program: String,
+    #[serde(default)]
+    pub options: CartridgeOptions,
+}
+
+/// Options of Octo which the program was written for. Options which do not matter here,
+/// such as the font or the screen rotation, are ignored.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CartridgeOptions {
+    /// Instructions per frame.
+    pub tickrate: Option<u32>,
+    pub background_color: Option<String>,
+    pub fill_color: Option<String>,
+    pub fill_color2: Option<String>,
+    pub blend_color: Option<String>,
+    pub shift_quirks: bool,
+    pub load_store_quirks: bool,
+    pub vf_order_quirks: bool,
+    pub clip_quirks: bool,
+    pub v_blank_quirks: bool,
+    pub jump_quirks: bool,
+    pub logic_quirks: bool,
+}
+
+impl CartridgeOptions {
+    /// Colours of the palette: background and foreground, then the second bitplane and
+    /// the blend of both bitplanes when they are set.
+    pub fn colors(&self) -> Option<Vec<String>> {
+        let background = self.background_color.clone()?;
+        let fill = self.fill_color.clone()?;
+
+        match (&self.fill_color2, &self.blend_color) {
+            (Some(fill2), Some(blend)) => Some(vec![background, fill, fill2.clone(), blend.clone()]),
+            _                          => Some(vec![background, fill]),
+        }
+    }
+
+    /// Names of the Octo quirks which are turned on.
+    pub fn quirks(&self) -> Vec<&'static str> {
+        let quirks = [
+            ("shiftQuirks", self.shift_quirks),
+            ("loadStoreQuirks", self.load_store_quirks),
+            ("vfOrderQuirks", self.vf_order_quirks),
+            ("clipQuirks", self.clip_quirks),
+            ("vBlankQuirks", self.v_blank_quirks),
+            ("jumpQuirks", self.jump_quirks),
+            ("logicQuirks", self.logic_quirks),
+        ];
+
+        quirks.iter()
+            .filter(|(_, is_on)| *is_on)
+            .map(|(name, _)| *name)
+            .collect()
+    }
+}
+
+/// Extract the program and options from the image of an Octo cartridge.
+pub fn decode(image: &[u8]) -> Result<Cartridge, String> {
+    let mut decoder = gif::DecodeOptions::new()
+        .read_info(image)
+        .map_err(|e| format!("Cannot read cartridge image: {}", e))?;
+    let frame = decoder.read_next_frame()
+        .map_err(|e| format!("Cannot read cartridge image: {}", e))?
+        .ok_or("Cartridge image has no frames.")?;
+
+    let bytes = frame.buffer.chunks_exact(4)
+        .map(|pixels| pixels.iter().fold(0, |byte, pixel| byte << 2 | pixel & 0b11))
+        .collect::<Vec<u8>>();
+
+    let length = match bytes.get(..4) {
+        Some(&[a, b, c, d]) => u32::from_be_bytes([a, b, c, d]) as usize,
+        _                   => return Err("Cartridge image is too small to hold a program.".to_owned()),
+    };
+    let payload = bytes.get(4..).and_then(|payload| payload.get(..length))
+        .ok_or("Cartridge image is too small to hold its program.")?;
+
+    serde_json::from_slice(payload)
+        .map_err(|e| format!("Cartridge image does not hold an Octo program: {}", e))
+}
+
+/// Image of a cartridge holding the payload, as Octo saves it.
+#[cfg(test)]
+pub fn cartridge_image(payload: &str) -> Vec<u8> {
+    let mut bytes = (payload.len() as u32).to_be_bytes().to_vec();
+    bytes.extend(payload.as_bytes());
+
+    let mut pixels = bytes.iter()
+        .flat_map(|byte| vec![byte >> 6, byte >> 4, byte >> 2, *byte])
+        .map(|bits| 0b1000 | bits & 0b11)
+        .collect::<Vec<u8>>();
+    // As many rows of 64 pixels as the payload takes, and at least 64 of them.
+    let width = 64;
+    let height = ((pixels.len() + 63) / 64).max(64) as u16;
+    pixels.resize(width as usize * height as usize, 0b1000);
+
+    let palette = (0..=255).flat_map(|index| vec![index, index, index]).collect::<Vec<u8>>();
+    let mut image = Vec::new();
+    {
+        let mut encoder = gif::Encoder::new(&mut image, width, height, &palette).unwrap();
+        encoder.write_frame(&gif::Frame::from_indexed_pixels(width, height, &pixels, None)).unwrap();
+    }
+
+    image
+}
+
+#[cfg(test)]
+#[path = "./cartridge_test.rs"]
+mod cartridge_test;
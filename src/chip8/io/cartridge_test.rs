@@ -0,0 +1,39 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "decode" {
+        test "extract the program and options" {
+            let image = cartridge_image(r##"{
+                "program": ": main\n  clear\n",
+                "options": {
+                    "tickrate": 20, "backgroundColor": "#996600", "fillColor": "#FFCC00",
+                    "fillColor2": "#FF6600", "blendColor": "#662200", "shiftQuirks": true,
+                    "fontStyle": "octo", "screenRotation": 0
+                }
+            }"##);
+
+            let cartridge = decode(&image).unwrap();
+
+            assert_eq!(cartridge.program, ": main\n  clear\n");
+            assert_eq!(cartridge.options.tickrate, Some(20));
+            assert_eq!(cartridge.options.colors().unwrap(), ["#996600", "#FFCC00", "#FF6600", "#662200"]);
+            assert_eq!(cartridge.options.quirks(), ["shiftQuirks"]);
+        }
+
+        test "use background and foreground colours without the second bitplane" {
+            let image = cartridge_image(r##"{"program": "", "options": {"backgroundColor": "#000000", "fillColor": "#FFFFFF"}}"##);
+
+            assert_eq!(decode(&image).unwrap().options.colors().unwrap(), ["#000000", "#FFFFFF"]);
+        }
+
+        test "reject images which are not cartridges" {
+            let image = cartridge_image("not an octo program");
+
+            assert!(decode(&image).is_err());
+            assert!(decode(b"GIF89a").is_err());
+        }
+    }
+}
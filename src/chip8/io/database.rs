@@ -0,0 +1,42 @@
+use std::collections::{BTreeMap, HashMap};
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use crate::chip8::platform::Platform;
+
+// Local copy of the program database, keyed by the SHA-1 hash of program files. It
+// holds an entry for each built-in program, and can be extended with any other program.
+lazy_static! {
+    static ref DATABASE: HashMap<String, ProgramInfo> =
+        serde_json::from_str(include_str!("../../../programs/database.json"))
+            .expect("Program database is not valid");
+}
+
+/// What is known about a program: where it comes from, and settings it runs best with.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProgramInfo {
+    pub title: String,
+    pub description: Option<String>,
+    pub authors: Vec<String>,
+    pub year: Option<u16>,
+    pub platform: Option<Platform>,
+    /// Which keys the program uses, as CHIP-8 keys and the keys added to the keymap.
+    pub controls: Option<String>,
+    /// What each CHIP-8 hex key does in the program, e.g. `Move left`.
+    pub actions: BTreeMap<String, String>,
+    pub quirks: Option<Vec<String>>,
+    pub speed: Option<u32>,
+    /// Keyboard keys (e.g. `Up` or `Space`) mapped to CHIP-8 hex keys which the program
+    /// uses, on top of the keymap.
+    pub keys: BTreeMap<String, String>,
+    pub colors: Option<Vec<String>>,
+}
+
+/// Look up a program by the SHA-1 hash of its file.
+pub fn lookup(sha1: &str) -> Option<ProgramInfo> {
+    DATABASE.get(sha1).cloned()
+}
+
+#[cfg(test)]
+#[path = "./database_test.rs"]
+mod database_test;
@@ -0,0 +1,54 @@
+#![cfg(test)]
+use speculate::speculate;
+use std::env::current_dir;
+use crate::built_in::BUILT_IN_PROGRAM_NAMES;
+use crate::chip8::cpu::Quirks;
+use crate::chip8::io::filesystem::Filesystem;
+use crate::chip8::io::keymap::{Keymap, KEYMAP_DEFAULT};
+use crate::chip8::io::overlay::ControlsHelp;
+
+use super::*;
+
+speculate! {
+    describe "lookup" {
+        test "find each built-in program" {
+            let filesystem = Filesystem::new(current_dir().unwrap());
+
+            for name in BUILT_IN_PROGRAM_NAMES.iter() {
+                let program = filesystem.load_built_in_program(name).unwrap();
+                let info = lookup(&program.sha1()).unwrap_or_else(|| panic!("program {}", name));
+
+                assert!(!info.title.is_empty(), "program {}", name);
+                assert_eq!(info.platform, Some(Platform::Chip8), "program {}", name);
+            }
+        }
+
+        test "find details of the program" {
+            let info = lookup("b232ef880bd6060fb45fa6effed7edf0ae95670e").unwrap();
+
+            assert_eq!(info.title, "Pong");
+            assert_eq!(info.authors, ["Paul Vervalin"]);
+            assert_eq!(info.year, Some(1990));
+        }
+
+        test "not find unknown programs" {
+            assert_eq!(lookup("0000000000000000000000000000000000000000"), None);
+        }
+
+        test "hold actions only for hex keys" {
+            let keymap = Keymap::preset(KEYMAP_DEFAULT).unwrap();
+
+            for (sha1, info) in DATABASE.iter() {
+                assert!(ControlsHelp::new(&info.actions, None, &keymap).is_ok(), "program {}", sha1);
+            }
+        }
+
+        test "hold only known quirks" {
+            for (sha1, info) in DATABASE.iter() {
+                let quirks = info.quirks.clone().unwrap_or_default();
+
+                assert!(Quirks::parse(&quirks).is_ok(), "program {}", sha1);
+            }
+        }
+    }
+}
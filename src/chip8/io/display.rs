@@ -1,64 +1,220 @@
+use std::collections::BTreeSet;
 use sdl2::Sdl;
-use sdl2::render::Canvas;
-use sdl2::video::Window;
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::pixels::Color;
 use sdl2::rect::Rect;
-use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT, WINDOW_TITLE, BG_COLOR, FG_COLOR};
+use sdl2::render::{BlendMode, Canvas};
+use sdl2::video::{FullscreenType, Window};
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT, WINDOW_TITLE};
+use crate::util::text::{wrap_text, FONT_SIZE, LINE_HEIGHT};
+use super::frontend::Screen;
+use super::overlay::{self, ControlsHelp, KeypadOverlay};
+use super::palette::Palette;
+use super::viewport::{Scaling, Viewport};
+
+const LETTERBOX_COLOR: Color = Color::BLACK;
+
+// Opacity of keys on the keypad overlay, so that the screen stays visible under them.
+const OVERLAY_KEY_ALPHA: u8 = 0x80;
+const OVERLAY_PRESSED_KEY_ALPHA: u8 = 0xD0;
+// Opacity of the controls help background, which dims the screen to keep text readable.
+const CONTROLS_HELP_ALPHA: u8 = 0xE0;
+const CONTROLS_HELP_TITLE: &str = "Controls (F1 to close)";
 
 pub struct Display {
     canvas: Canvas<Window>,
-    scale: u32,
+    scaling: Scaling,
+    palette: Palette,
+    overlay: KeypadOverlay,
+    controls_help: ControlsHelp,
+    // Size of the window before switching to fullscreen, so that it can be restored.
+    windowed_size: (u32, u32),
 }
 
 impl Display {
-    pub fn new(sdl: &Sdl, title: String, scale: u32) -> Result<Self, String> {
+    pub fn new(sdl: &Sdl, title: String, size: (u32, u32), scaling: Scaling, palette: Palette, overlay: KeypadOverlay, controls_help: ControlsHelp) -> Result<Self, String> {
         let title = format!("{} - {}", WINDOW_TITLE, title.to_uppercase());
-        let width = (CHIP8_WIDTH as u32) * scale;
-        let height = (CHIP8_HEIGHT as u32) * scale;
+        let (width, height) = size;
 
         let video = sdl.video()?;
         let window = video
             .window(&title, width, height)
             .position_centered()
+            .resizable()
             .opengl()
             .build()
             .map_err(|e| e.to_string())?;
-        let canvas = window
+        let mut canvas = window
             .into_canvas()
             .build()
             .map_err(|e| e.to_string())?;
+        canvas.set_blend_mode(BlendMode::Blend);
 
         let mut display = Display {
             canvas,
-            scale,
+            scaling,
+            palette,
+            overlay,
+            controls_help,
+            windowed_size: size,
         };
-        display.clear();
+        display.clear()?;
 
-        debug!("Initialised display: width={}, height={}, scale={}", width, height, scale);
+        debug!("Initialised display: width={}, height={}, scaling={:?}, palette={}", width, height, scaling, display.palette.name);
 
         Ok(display)
     }
 
-    fn clear(&mut self) {
-        self.canvas.set_draw_color(BG_COLOR);
-        self.canvas.clear();
-        self.canvas.present();
+    fn clear(&mut self) -> Result<(), String> {
+        self.render(&[[false; CHIP8_WIDTH]; CHIP8_HEIGHT])
+    }
+
+    fn viewport(&self) -> Result<Viewport, String> {
+        let (width, height) = self.canvas.output_size()?;
+
+        Ok(Viewport::new(width, height, self.scaling))
+    }
+
+    fn render_overlay(&mut self, viewport: &Viewport) -> Result<(), String> {
+        let background = self.palette.background();
+        let foreground = self.palette.foreground();
+
+        for (chip8_keycode, rect) in overlay::key_rects(viewport) {
+            let is_pressed = self.overlay.is_pressed(chip8_keycode);
+            let (fill, text) = if is_pressed {
+                (Color::RGBA(foreground.r, foreground.g, foreground.b, OVERLAY_PRESSED_KEY_ALPHA), background)
+            } else {
+                (Color::RGBA(background.r, background.g, background.b, OVERLAY_KEY_ALPHA), foreground)
+            };
+
+            self.canvas.set_draw_color(fill);
+            self.canvas.fill_rect(rect)?;
+            self.canvas.set_draw_color(foreground);
+            self.canvas.draw_rect(rect)?;
+
+            // Hex digit of the CHIP-8 key, with the keyboard key below it.
+            let lines = [format!("{:X}", chip8_keycode), self.overlay.label(chip8_keycode).to_owned()];
+            let center = rect.center();
+            for (index, line) in lines.iter().enumerate() {
+                let x = center.x() - line.len() as i32 * FONT_SIZE / 2;
+                let y = center.y() - FONT_SIZE + index as i32 * (FONT_SIZE + 2);
+                self.canvas.string(x as i16, y as i16, line, text)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Lines of the help in the top left corner of the viewport, over a dimmed screen.
+    // Lines which do not fit into the viewport are left out.
+    fn render_controls_help(&mut self, viewport: &Viewport) -> Result<(), String> {
+        let background = self.palette.background();
+        let foreground = self.palette.foreground();
+
+        self.canvas.set_draw_color(Color::RGBA(background.r, background.g, background.b, CONTROLS_HELP_ALPHA));
+        self.canvas.fill_rect(Rect::new(viewport.x, viewport.y, viewport.width, viewport.height))?;
+
+        let columns = ((viewport.width as i32 - 2 * FONT_SIZE) / FONT_SIZE).max(1) as usize;
+        let rows = ((viewport.height as i32 - FONT_SIZE) / LINE_HEIGHT).max(0) as usize;
+        let mut lines = vec![CONTROLS_HELP_TITLE.to_owned(), String::new()];
+        lines.extend(self.controls_help.lines().iter().flat_map(|line| wrap_text(line, columns)));
+
+        for (row, line) in lines.iter().take(rows).enumerate() {
+            let x = viewport.x + FONT_SIZE;
+            let y = viewport.y + FONT_SIZE + row as i32 * LINE_HEIGHT;
+            self.canvas.string(x as i16, y as i16, line, foreground)?;
+        }
+
+        Ok(())
+    }
+
+    /// Size of the window when it is not in fullscreen mode.
+    pub fn windowed_size(&self) -> (u32, u32) {
+        let window = self.canvas.window();
+
+        match window.fullscreen_state() {
+            FullscreenType::Off => window.size(),
+            _                   => self.windowed_size,
+        }
     }
+}
+
+impl Screen for Display {
+    fn render(&mut self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT]) -> Result<(), String> {
+        let viewport = self.viewport()?;
+
+        self.canvas.set_draw_color(LETTERBOX_COLOR);
+        self.canvas.clear();
 
-    pub fn render(&mut self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT]) -> Result<(), String> {
         for (y, &row) in pixels.iter().enumerate() {
             for (x, &pixel) in row.iter().enumerate() {
-                let x = (x as u32) * self.scale;
-                let y = (y as u32) * self.scale;
-                let color = if pixel { FG_COLOR } else { BG_COLOR };
-
-                let pixel_scaled = Rect::new(x as i32, y as i32, self.scale, self.scale);
+                let color = if pixel { self.palette.foreground() } else { self.palette.background() };
 
                 self.canvas.set_draw_color(color);
-                self.canvas.fill_rect(pixel_scaled)?;
+                self.canvas.fill_rect(viewport.pixel_rect(x, y))?;
             }
         }
 
+        if self.overlay.is_visible {
+            self.render_overlay(&viewport)?;
+        }
+        if self.controls_help.is_visible {
+            self.render_controls_help(&viewport)?;
+        }
+
         self.canvas.present();
         Ok(())
     }
+
+    fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
+    fn toggle_fullscreen(&mut self) -> Result<(), String> {
+        let window = self.canvas.window_mut();
+
+        let fullscreen = match window.fullscreen_state() {
+            FullscreenType::Off => {
+                self.windowed_size = window.size();
+                FullscreenType::Desktop
+            },
+            _ => FullscreenType::Off,
+        };
+
+        debug!("Switched fullscreen mode: {:?}", fullscreen);
+
+        window.set_fullscreen(fullscreen)
+    }
+
+    fn pixel_scale(&self) -> u32 {
+        self.viewport()
+            .map(|viewport| viewport.width / CHIP8_WIDTH as u32)
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    fn toggle_keypad_overlay(&mut self) {
+        self.overlay.toggle();
+    }
+
+    fn toggle_controls_help(&mut self) {
+        self.controls_help.toggle();
+    }
+
+    fn set_pressed_keycodes(&mut self, pressed_keycodes: &BTreeSet<u8>) -> bool {
+        self.overlay.set_pressed_keycodes(pressed_keycodes)
+    }
+
+    fn keycode_at(&self, x: i32, y: i32) -> Option<u8> {
+        let viewport = self.viewport().ok()?;
+
+        // Mouse position is in window coordinates, which differ from pixels of the
+        // canvas on high DPI displays.
+        let (window_width, window_height) = self.canvas.window().size();
+        let (width, height) = self.canvas.output_size().ok()?;
+        let x = x * width as i32 / window_width.max(1) as i32;
+        let y = y * height as i32 / window_height.max(1) as i32;
+
+        self.overlay.key_at(&viewport, x, y)
+    }
 }
@@ -1,15 +1,66 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::path::PathBuf;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 use std::env::current_dir;
+use sha1::Sha1;
+use zip::ZipArchive;
 use crate::args::ProgramArg;
+use crate::built_in;
+use crate::chip8::constants::PROGRAM_ADDRESS_START;
+use crate::chip8::octo;
+use crate::chip8::platform::Platform;
+use super::cartridge;
+use super::database::{self, ProgramInfo};
 
-const PROGRAM_SIZE_MAX: usize = 3584;
+// Extensions of CHIP-8, SUPER-CHIP and XO-CHIP program files in directories and zip
+// archives.
+const PROGRAM_EXTENSIONS: [&str; 3] = ["ch8", "sc8", "xo8"];
 
+// Octo cartridges hold the source of the program in a GIF image, so they are read up to
+// a larger size than programs.
+const CARTRIDGE_SIZE_MAX: u64 = 16 * 1024 * 1024;
+
+/// Program file path which stands for standard input or output, e.g. to pipe assembler
+/// output into the interpreter.
+pub const STDIO_PATH: &str = "-";
+
+#[derive(Debug)]
 pub struct Program {
     pub name: String,
-    pub rom: [u8; PROGRAM_SIZE_MAX],
-    pub size: usize,
+    pub rom: Vec<u8>,
+    /// Entry of the program in the program database, if it is there, or the settings
+    /// which an Octo cartridge holds.
+    pub info: Option<ProgramInfo>,
+}
+
+impl Program {
+    /// SHA-1 hash of the program as a hex string, which identifies it whatever its name.
+    pub fn sha1(&self) -> String {
+        Sha1::from(&self.rom).digest().to_string()
+    }
+
+    /// Check that the program fits into the memory of the platform, after the
+    /// interpreter area.
+    pub fn check_size(&self, platform: Platform) -> Result<(), String> {
+        self.check_size_at(platform, PROGRAM_ADDRESS_START)
+    }
+
+    /// Check that the program fits into the memory of the platform, when it is loaded
+    /// at the address.
+    pub fn check_size_at(&self, platform: Platform, load_address: usize) -> Result<(), String> {
+        let size_max = (PROGRAM_ADDRESS_START + platform.program_size_max()).saturating_sub(load_address);
+
+        if self.rom.is_empty() {
+            return Err(format!("Program {} is empty.", self.name));
+        }
+        if self.rom.len() > size_max {
+            return Err(format!("Program {} is {} bytes, which does not fit into the {} bytes of {} program space.",
+                self.name, self.rom.len(), size_max, platform));
+        }
+
+        Ok(())
+    }
 }
 
 pub struct Filesystem {
@@ -31,77 +82,233 @@ impl Filesystem {
     }
 
     pub fn load_program(&self, arg: ProgramArg) -> Result<Program, String> {
-        let program = match arg {
+        let mut program = match arg {
             ProgramArg::BuiltInProgram(name) => self.load_built_in_program(&name),
             ProgramArg::ProgramFile(path)    => self.load_program_file(&path),
         }?;
+        program.info = database::lookup(&program.sha1()).or(program.info);
 
-        debug!("Loaded program: name={}, size={}B", program.name, program.size);
+        debug!("Loaded program: name={}, size={}B, info={:?}", program.name, program.rom.len(), program.info);
 
         Ok(program)
     }
 
     pub fn load_built_in_program(&self, name: &str) -> Result<Program, String> {
-        let data = match name {
-            "15puzzle" => &include_bytes!("../../../programs/15puzzle")[..],
-            "blinky"   => &include_bytes!("../../../programs/blinky")[..],
-            "blitz"    => &include_bytes!("../../../programs/blitz")[..],
-            "brix"     => &include_bytes!("../../../programs/brix")[..],
-            "connect4" => &include_bytes!("../../../programs/connect4")[..],
-            "guess"    => &include_bytes!("../../../programs/guess")[..],
-            "hidden"   => &include_bytes!("../../../programs/hidden")[..],
-            "invaders" => &include_bytes!("../../../programs/invaders")[..],
-            "kaleid"   => &include_bytes!("../../../programs/kaleid")[..],
-            "maze"     => &include_bytes!("../../../programs/maze")[..],
-            "merlin"   => &include_bytes!("../../../programs/merlin")[..],
-            "missile"  => &include_bytes!("../../../programs/missile")[..],
-            "pong"     => &include_bytes!("../../../programs/pong")[..],
-            "pong2"    => &include_bytes!("../../../programs/pong2")[..],
-            "puzzle"   => &include_bytes!("../../../programs/puzzle")[..],
-            "syzygy"   => &include_bytes!("../../../programs/syzygy")[..],
-            "tank"     => &include_bytes!("../../../programs/tank")[..],
-            "tetris"   => &include_bytes!("../../../programs/tetris")[..],
-            "tictac"   => &include_bytes!("../../../programs/tictac")[..],
-            "ufo"      => &include_bytes!("../../../programs/ufo")[..],
-            "vbrix"    => &include_bytes!("../../../programs/vbrix")[..],
-            "vers"     => &include_bytes!("../../../programs/vers")[..],
-            "wipeoff"  => &include_bytes!("../../../programs/wipeoff")[..],
-            _          => return Err(format!("Unknown program: {}.", name)),
-        };
-        let size = data.len();
+        let data = built_in::find(name)
+            .map(|program| program.rom)
+            .ok_or_else(|| format!("Unknown program: {}.", name))?;
 
-        let mut rom = [0u8; PROGRAM_SIZE_MAX];
-        rom[0..size].copy_from_slice(data);
-
-        debug!("Loaded built-in program: name={}, size={}", name, size);
+        debug!("Loaded built-in program: name={}, size={}", name, data.len());
 
         Ok(Program {
             name: name.to_owned(),
-            rom,
-            size,
+            rom: data.to_vec(),
+            info: None,
         })
     }
 
+    /// Load the whole program file. Files larger than the program space of any platform
+    /// are rejected, rather than cut short.
+    ///
+    /// The path can also point at a directory or a zip archive which holds a single
+    /// program file, or at a program file inside a zip archive, e.g. `pack.zip/pong.ch8`.
+    /// The program is read from standard input when the path is `-`.
     pub fn load_program_file(&self, path: &str) -> Result<Program, String> {
-        let path = self.root.join(path);
-        let name = path.file_stem()
+        if path == STDIO_PATH {
+            return read_program("stdin", io::stdin().lock());
+        }
+
+        let full_path = self.root.join(path);
+
+        if full_path.is_dir() || (full_path.is_file() && is_zip(&full_path)) {
+            let program_files = self.list_program_files(path)?;
+
+            return match program_files.as_slice() {
+                [program_file] => self.load_program_file(program_file),
+                []             => Err(format!("No program files in {}.", path)),
+                _              => Err(format!("Several program files in {}, pick one of: {}.", path, program_files.join(", "))),
+            };
+        }
+
+        let name = full_path.file_stem()
             .and_then(|stem| stem.to_str())
             .ok_or("Cannot extract program name from file path")?;
 
-        let mut file = File::open(&path)
-            .map_err(|e| e.to_string())?;
+        let rom = match split_zip_path(&full_path) {
+            Some((archive, entry)) if !full_path.exists() => read_zip_entry(archive, &entry)?,
+            _ => read_program_file(&full_path)?,
+        };
 
-        let mut rom = [0u8; PROGRAM_SIZE_MAX];
-        let size = file.read(&mut rom)
-            .map_err(|e| e.to_string())?;
+        debug!("Loaded program from file: name={}, size={}, path={:?}", name, rom.len(), full_path);
 
-        debug!("Loaded program from file: name={}, size={}, path={:?}", name, size, path);
+        let program = if has_extension(&full_path, &["gif"]) {
+            load_cartridge(name, &rom)?
+        } else {
+            Program {
+                name: name.to_owned(),
+                rom,
+                info: None,
+            }
+        };
+        program.check_size(Platform::XoChip)?;
 
-        Ok(Program {
-            name: name.to_owned(),
-            rom,
-            size,
-        })
+        Ok(program)
+    }
+
+    /// Paths of the program files (`.ch8`, `.sc8` or `.xo8`) in a directory or a zip
+    /// archive, in order, which can be loaded with `load_program_file`.
+    pub fn list_program_files(&self, path: &str) -> Result<Vec<String>, String> {
+        let full_path = self.root.join(path);
+
+        let mut names = if full_path.is_dir() {
+            fs::read_dir(&full_path)
+                .map_err(|e| format!("Cannot read directory {:?}: {}", full_path, e))?
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_file())
+                .filter_map(|entry| entry.file_name().to_str().map(str::to_owned))
+                .collect::<Vec<_>>()
+        } else if is_zip(&full_path) {
+            let archive = open_zip(&full_path)?;
+            archive.file_names()
+                .filter(|name| !name.ends_with('/') && !name.starts_with("__MACOSX/"))
+                .map(str::to_owned)
+                .collect()
+        } else {
+            return Err(format!("{} is neither a directory nor a zip archive.", path));
+        };
+
+        names.retain(|name| has_extension(Path::new(name), &PROGRAM_EXTENSIONS));
+        names.sort();
+
+        Ok(names.iter()
+            .map(|name| Path::new(path).join(name).to_string_lossy().into_owned())
+            .collect())
+    }
+
+    /// Modification time and size of the file, which change when it is written to. For
+    /// programs inside zip archives, it is the archive which is checked.
+    pub fn file_version(&self, path: &str) -> Option<(SystemTime, u64)> {
+        let full_path = self.root.join(path);
+
+        if full_path.is_dir() {
+            return match self.list_program_files(path).ok()?.as_slice() {
+                [program_file] => self.file_version(program_file),
+                _              => None,
+            };
+        }
+
+        let file_path = match split_zip_path(&full_path) {
+            Some((archive, _)) if !full_path.exists() => archive,
+            _                                         => &full_path,
+        };
+        let metadata = fs::metadata(file_path).ok()?;
+        let modified = metadata.modified().ok()?;
+
+        Some((modified, metadata.len()))
+    }
+}
+
+// Compile the program of an Octo cartridge, with its options as the settings of the
+// program.
+fn load_cartridge(name: &str, image: &[u8]) -> Result<Program, String> {
+    let cartridge = cartridge::decode(image)?;
+    let rom = octo::compile(&cartridge.program)
+        .map_err(|e| format!("Cannot compile the program of cartridge {}: {}", name, e))?;
+
+    let quirks = cartridge.options.quirks();
+    if !quirks.is_empty() {
+        warn!("Cartridge {} turns on Octo quirks which are not supported, so it may not run correctly: {}.", name, quirks.join(", "));
+    }
+
+    let info = ProgramInfo {
+        title: name.to_owned(),
+        speed: cartridge.options.tickrate,
+        colors: cartridge.options.colors(),
+        ..ProgramInfo::default()
+    };
+
+    Ok(Program { name: name.to_owned(), rom, info: Some(info) })
+}
+
+// Read the whole program from a stream, up to the size which any program can take.
+fn read_program(name: &str, reader: impl Read) -> Result<Program, String> {
+    let mut rom = Vec::new();
+    reader.take(Platform::XoChip.program_size_max() as u64 + 1)
+        .read_to_end(&mut rom)
+        .map_err(|e| format!("Cannot read program from {}: {}", name, e))?;
+
+    debug!("Loaded program from {}: size={}", name, rom.len());
+
+    let program = Program { name: name.to_owned(), rom, info: None };
+    program.check_size(Platform::XoChip)?;
+
+    Ok(program)
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) => extensions.iter().any(|known| extension.eq_ignore_ascii_case(known)),
+        None            => false,
+    }
+}
+
+fn is_zip(path: &Path) -> bool {
+    has_extension(path, &["zip"])
+}
+
+// Split a path to a file inside a zip archive, e.g. `roms/pack.zip/games/pong.ch8`,
+// into the path of the archive and the name of the entry in the archive.
+fn split_zip_path(path: &Path) -> Option<(&Path, String)> {
+    let archive = path.ancestors()
+        .skip(1)
+        .find(|ancestor| is_zip(ancestor) && ancestor.is_file())?;
+
+    let entry = path.strip_prefix(archive).ok()?
+        .iter()
+        .map(|component| component.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+
+    Some((archive, entry))
+}
+
+fn open_zip(path: &Path) -> Result<ZipArchive<File>, String> {
+    let file = File::open(path)
+        .map_err(|e| format!("Cannot open zip archive {:?}: {}", path, e))?;
+
+    ZipArchive::new(file)
+        .map_err(|e| format!("Cannot read zip archive {:?}: {}", path, e))
+}
+
+fn read_zip_entry(archive: &Path, name: &str) -> Result<Vec<u8>, String> {
+    let mut zip = open_zip(archive)?;
+    let entry = zip.by_name(name)
+        .map_err(|e| format!("Cannot find {} in zip archive {:?}: {}", name, archive, e))?;
+
+    let mut rom = Vec::new();
+    entry.take(file_size_max(Path::new(name)))
+        .read_to_end(&mut rom)
+        .map_err(|e| format!("Cannot read {} from zip archive {:?}: {}", name, archive, e))?;
+
+    Ok(rom)
+}
+
+fn read_program_file(path: &Path) -> Result<Vec<u8>, String> {
+    let mut rom = Vec::new();
+    File::open(path)
+        .and_then(|file| file.take(file_size_max(path)).read_to_end(&mut rom))
+        .map_err(|e| format!("Cannot read program file {:?}: {}", path, e))?;
+
+    Ok(rom)
+}
+
+// One byte more than any program can take, so that larger programs are rejected without
+// reading them whole.
+fn file_size_max(path: &Path) -> u64 {
+    if has_extension(path, &["gif"]) {
+        CARTRIDGE_SIZE_MAX + 1
+    } else {
+        Platform::XoChip.program_size_max() as u64 + 1
     }
 }
 
@@ -1,30 +1,34 @@
 #![cfg(test)]
 use speculate::speculate;
-use tempfile::NamedTempFile;
+use tempfile::{NamedTempFile, TempDir};
+use zip::write::{FileOptions, ZipWriter};
 use std::env::current_dir;
-use std::io::Error;
+use std::io::{Error, Write};
+use crate::built_in::BUILT_IN_PROGRAM_NAMES;
+use crate::chip8::io::cartridge::cartridge_image;
 
 use super::*;
 
 speculate! {
     describe "load_built_in_program" {
-        test "load each one of 23 programs" {
-            let programs: [(&str, usize); 23] = [
-                ("15puzzle", 384), ("blinky", 2356), ("blitz", 391), ("brix", 280), ("connect4", 194),
-                ("guess", 148), ("hidden", 850), ("invaders", 1283), ("kaleid", 120), ("maze", 34),
-                ("merlin", 345), ("missile", 180), ("pong", 246), ("pong2", 264), ("puzzle", 184),
-                ("syzygy", 946), ("tank", 560), ("tetris", 494), ("tictac", 486), ("ufo", 224),
-                ("vbrix", 507), ("vers", 230), ("wipeoff", 206)
-            ];
+        test "load each program from the registry" {
             let filesystem = build_filesystem();
 
-            for &(name, size) in programs.iter() {
+            for name in BUILT_IN_PROGRAM_NAMES.iter() {
                 let program = filesystem.load_built_in_program(name).unwrap();
+                let file = ["", ".ch8"].iter()
+                    .find_map(|extension| fs::read(format!("programs/{}{}", name, extension)).ok())
+                    .unwrap();
 
-                assert_eq!(program.name, name);
-                assert_eq!(program.size, size);
+                assert_eq!(program.name, *name);
+                assert_eq!(program.rom, file, "program {}", name);
+                assert!(!program.rom.is_empty(), "program {}", name);
             }
         }
+
+        test "reject unknown programs" {
+            assert!(build_filesystem().load_built_in_program("unknown").is_err());
+        }
     }
 
     describe "load_program_file" {
@@ -37,7 +41,163 @@ speculate! {
             let filesystem = build_filesystem();
             let program = filesystem.load_program_file(path).unwrap();
 
-            assert_eq!(program.size, size);
+            assert_eq!(program.rom.len(), size);
+        }
+
+        test "load programs larger than CHIP-8 program space whole" {
+            let file = save_to_temp_file(&[0xAA; 5000]).unwrap();
+            let path = file.path().to_str().unwrap();
+
+            let program = build_filesystem().load_program_file(path).unwrap();
+
+            assert_eq!(program.rom.len(), 5000);
+        }
+
+        test "reject programs larger than XO-CHIP program space" {
+            let file = save_to_temp_file(&[0xAA; 0x10000]).unwrap();
+            let path = file.path().to_str().unwrap();
+
+            assert!(build_filesystem().load_program_file(path).is_err());
+        }
+
+        test "load Octo cartridges larger than XO-CHIP program space" {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("game.gif");
+            // Pseudo-random letters, which the GIF encoder cannot compress much.
+            let mut seed = 1u32;
+            let comment: String = (0..0x20000)
+                .map(|_| {
+                    seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                    (b'a' + (seed >> 16) as u8 % 26) as char
+                })
+                .collect();
+            let image = cartridge_image(&format!(r#"{{"program": ": main clear # {}"}}"#, comment));
+            assert!(image.len() > 0x10000);
+            fs::write(&path, image).unwrap();
+
+            assert!(build_filesystem().load_program_file(path.to_str().unwrap()).is_ok());
+        }
+
+        test "compile the program of an Octo cartridge and keep its options" {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("game.gif");
+            let image = cartridge_image(r##"{"program": ": main clear", "options": {"tickrate": 15, "backgroundColor": "#000000", "fillColor": "#FFFFFF"}}"##);
+            fs::write(&path, image).unwrap();
+
+            let program = build_filesystem().load_program_file(path.to_str().unwrap()).unwrap();
+
+            assert_eq!(program.name, "game");
+            assert_eq!(program.rom, [0x12, 0x02, 0x00, 0xE0]);
+            let info = program.info.unwrap();
+            assert_eq!(info.speed, Some(15));
+            assert_eq!(info.colors.unwrap(), ["#000000", "#FFFFFF"]);
+        }
+    }
+
+    describe "directories and zip archives" {
+        test "load the single program file in a directory" {
+            let dir = TempDir::new().unwrap();
+            fs::write(dir.path().join("maze.ch8"), [0x00, 0xE0]).unwrap();
+            fs::write(dir.path().join("readme.txt"), b"Maze").unwrap();
+
+            let program = build_filesystem().load_program_file(dir.path().to_str().unwrap()).unwrap();
+
+            assert_eq!(program.name, "maze");
+            assert_eq!(program.rom, [0x00, 0xE0]);
+        }
+
+        test "list the program files when there are several" {
+            let dir = TempDir::new().unwrap();
+            fs::write(dir.path().join("b.sc8"), [0x00, 0xE0]).unwrap();
+            fs::write(dir.path().join("a.ch8"), [0x00, 0xE0]).unwrap();
+            let path = dir.path().to_str().unwrap();
+
+            let filesystem = build_filesystem();
+            let program_files = filesystem.list_program_files(path).unwrap();
+
+            assert_eq!(program_files, [format!("{}/a.ch8", path), format!("{}/b.sc8", path)]);
+            assert!(filesystem.load_program_file(path).unwrap_err().contains("a.ch8"));
+        }
+
+        test "load a program file inside a zip archive" {
+            let dir = TempDir::new().unwrap();
+            let archive = save_to_zip(&dir, &[("games/pong.ch8", &[0x12, 0x00]), ("maze.ch8", &[0x00, 0xE0])]);
+
+            let filesystem = build_filesystem();
+            let program = filesystem.load_program_file(&format!("{}/games/pong.ch8", archive)).unwrap();
+
+            assert_eq!(program.name, "pong");
+            assert_eq!(program.rom, [0x12, 0x00]);
+            assert_eq!(filesystem.list_program_files(&archive).unwrap(),
+                [format!("{}/games/pong.ch8", archive), format!("{}/maze.ch8", archive)]);
+        }
+
+        test "load the single program file in a zip archive" {
+            let dir = TempDir::new().unwrap();
+            let archive = save_to_zip(&dir, &[("pong.ch8", &[0x12, 0x00]), ("pong.txt", b"Pong")]);
+
+            let program = build_filesystem().load_program_file(&archive).unwrap();
+
+            assert_eq!(program.name, "pong");
+        }
+
+        test "report programs missing from a zip archive" {
+            let dir = TempDir::new().unwrap();
+            let archive = save_to_zip(&dir, &[("pong.ch8", &[0x12, 0x00])]);
+
+            assert!(build_filesystem().load_program_file(&format!("{}/tetris.ch8", archive)).is_err());
+        }
+    }
+
+    describe "read_program" {
+        test "read the whole program from the stream" {
+            let rom = [0x12, 0x00, 0x00, 0xE0];
+
+            let program = read_program("stdin", &rom[..]).unwrap();
+
+            assert_eq!(program.name, "stdin");
+            assert_eq!(program.rom, rom);
+        }
+
+        test "reject empty and too large programs" {
+            assert!(read_program("stdin", &[][..]).is_err());
+            assert!(read_program("stdin", &vec![0; 0x10000][..]).is_err());
+        }
+    }
+
+    describe "check_size" {
+        test "accept programs which fit into program space" {
+            let program = Program { name: "full".to_owned(), rom: vec![0; 3584], info: None };
+
+            assert!(program.check_size(Platform::Chip8).is_ok());
+        }
+
+        test "reject programs which do not fit into program space" {
+            let program = Program { name: "large".to_owned(), rom: vec![0; 3585], info: None };
+
+            assert!(program.check_size(Platform::Chip8).is_err());
+            assert!(program.check_size(Platform::XoChip).is_ok());
+        }
+
+        test "reject empty programs" {
+            let program = Program { name: "empty".to_owned(), rom: Vec::new(), info: None };
+
+            assert!(program.check_size(Platform::Chip8).is_err());
+        }
+
+        test "leave less space for programs loaded at a later address" {
+            let program = Program { name: "eti".to_owned(), rom: vec![0; 2560], info: None };
+
+            assert!(program.check_size_at(Platform::Chip8, 0x600).is_ok());
+            assert!(program.check_size_at(Platform::Chip8, 0x602).is_err());
+        }
+    }
+
+    describe "sha1" {
+        test "hash the program" {
+            let program = build_filesystem().load_built_in_program("maze").unwrap();
+
+            assert_eq!(program.sha1(), "b9272ae1acdaaa79ab649f6b48b72088ca2b1d74");
         }
     }
 
@@ -46,6 +206,19 @@ speculate! {
         Filesystem::new(root)
     }
 
+    fn save_to_zip(dir: &TempDir, files: &[(&str, &[u8])]) -> String {
+        let path = dir.path().join("pack.zip");
+        let mut zip = ZipWriter::new(fs::File::create(&path).unwrap());
+
+        for &(name, bytes) in files.iter() {
+            zip.start_file(name, FileOptions::default()).unwrap();
+            zip.write_all(bytes).unwrap();
+        }
+        zip.finish().unwrap();
+
+        path.to_str().unwrap().to_owned()
+    }
+
     fn save_to_temp_file(bytes: &[u8]) -> Result<NamedTempFile, Error> {
         let mut tmp_program_file = NamedTempFile::new()?;
         tmp_program_file.write_all(bytes)?;
@@ -0,0 +1,57 @@
+use std::collections::BTreeSet;
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+use crate::chip8::interface::KeyEvent;
+use super::keypad::KeypadState;
+use super::palette::Palette;
+
+// Traits which a frontend implements to run CHIP-8 programs. The SDL frontend draws
+// into a window, while the terminal frontend draws with text characters.
+
+pub trait Screen {
+    fn render(&mut self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT]) -> Result<(), String>;
+
+    fn set_palette(&mut self, palette: Palette);
+
+    fn toggle_fullscreen(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+
+    /// Number of screen pixels which one CHIP-8 pixel takes in each direction.
+    fn pixel_scale(&self) -> u32 {
+        1
+    }
+
+    fn toggle_keypad_overlay(&mut self) {}
+
+    /// Show or hide what the keys do in the program.
+    fn toggle_controls_help(&mut self) {}
+
+    /// Show which CHIP-8 keys are pressed. Returns whether the screen needs to be
+    /// drawn again.
+    fn set_pressed_keycodes(&mut self, _pressed_keycodes: &BTreeSet<u8>) -> bool {
+        false
+    }
+
+    /// CHIP-8 key on the screen at the point in the window, e.g. on the keypad overlay.
+    fn keycode_at(&self, _x: i32, _y: i32) -> Option<u8> {
+        None
+    }
+}
+
+pub trait Controls {
+    fn state(&mut self) -> KeypadState;
+
+    /// Hold the CHIP-8 key down with the mouse or by touch, or release the one which is
+    /// held. Returns CHIP-8 keys which went down or up, given the keys held otherwise.
+    fn set_pointer_keycode(&mut self, _chip8_keycode: Option<u8>) -> Vec<KeyEvent> {
+        Vec::new()
+    }
+}
+
+pub trait Buzzer {
+    /// Play a 60Hz frame, given whether the buzzer was on after each instruction of it.
+    fn play_frame(&mut self, sound_states: &[bool]);
+
+    /// Silence the buzzer, or let it sound again. Returns whether it is muted now.
+    fn toggle_mute(&mut self) -> bool;
+}
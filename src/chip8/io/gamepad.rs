@@ -0,0 +1,227 @@
+use std::collections::{BTreeMap, HashMap};
+use sdl2::{GameControllerSubsystem, Sdl};
+use sdl2::controller::{Axis, Button, GameController};
+use sdl2::event::Event;
+
+pub const GAMEPAD_MAP_DEFAULT: &str = "default";
+
+// Sticks and triggers count as pressed when moved at least halfway.
+const AXIS_THRESHOLD: i16 = i16::MAX / 2;
+
+/// Button, or direction of a stick or trigger, on a game controller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadInput {
+    Button(Button),
+    AxisNegative(Axis),
+    AxisPositive(Axis),
+}
+
+// Names follow SDL game controller mapping strings, with `-` and `+` for directions of
+// sticks (up and left are negative).
+const INPUT_NAMES: [(&str, GamepadInput); 25] = [
+    ("a",             GamepadInput::Button(Button::A)),
+    ("b",             GamepadInput::Button(Button::B)),
+    ("x",             GamepadInput::Button(Button::X)),
+    ("y",             GamepadInput::Button(Button::Y)),
+    ("back",          GamepadInput::Button(Button::Back)),
+    ("guide",         GamepadInput::Button(Button::Guide)),
+    ("start",         GamepadInput::Button(Button::Start)),
+    ("leftstick",     GamepadInput::Button(Button::LeftStick)),
+    ("rightstick",    GamepadInput::Button(Button::RightStick)),
+    ("leftshoulder",  GamepadInput::Button(Button::LeftShoulder)),
+    ("rightshoulder", GamepadInput::Button(Button::RightShoulder)),
+    ("dpup",          GamepadInput::Button(Button::DPadUp)),
+    ("dpdown",        GamepadInput::Button(Button::DPadDown)),
+    ("dpleft",        GamepadInput::Button(Button::DPadLeft)),
+    ("dpright",       GamepadInput::Button(Button::DPadRight)),
+    ("leftx-",        GamepadInput::AxisNegative(Axis::LeftX)),
+    ("leftx+",        GamepadInput::AxisPositive(Axis::LeftX)),
+    ("lefty-",        GamepadInput::AxisNegative(Axis::LeftY)),
+    ("lefty+",        GamepadInput::AxisPositive(Axis::LeftY)),
+    ("rightx-",       GamepadInput::AxisNegative(Axis::RightX)),
+    ("rightx+",       GamepadInput::AxisPositive(Axis::RightX)),
+    ("righty-",       GamepadInput::AxisNegative(Axis::RightY)),
+    ("righty+",       GamepadInput::AxisPositive(Axis::RightY)),
+    ("lefttrigger",   GamepadInput::AxisPositive(Axis::TriggerLeft)),
+    ("righttrigger",  GamepadInput::AxisPositive(Axis::TriggerRight)),
+];
+
+// Presets named after built-in programs are used for those programs by default, the
+// `default` preset suits programs which move with 2/4/6/8 and act with 5.
+const PRESETS: [(&str, &[(&str, u8)]); 6] = [
+    ("default", &[
+        ("dpup", 0x2), ("dpdown", 0x8), ("dpleft", 0x4), ("dpright", 0x6),
+        ("lefty-", 0x2), ("lefty+", 0x8), ("leftx-", 0x4), ("leftx+", 0x6),
+        ("a", 0x5), ("b", 0x0),
+    ]),
+    ("pong", &[
+        ("dpup", 0x1), ("dpdown", 0x4), ("lefty-", 0x1), ("lefty+", 0x4),
+        ("righty-", 0xC), ("righty+", 0xD), ("y", 0xC), ("a", 0xD),
+    ]),
+    ("pong2", &[
+        ("dpup", 0x1), ("dpdown", 0x4), ("lefty-", 0x1), ("lefty+", 0x4),
+        ("righty-", 0xC), ("righty+", 0xD), ("y", 0xC), ("a", 0xD),
+    ]),
+    ("tetris", &[
+        ("dpleft", 0x5), ("dpright", 0x6), ("dpdown", 0x7), ("dpup", 0x4),
+        ("leftx-", 0x5), ("leftx+", 0x6), ("lefty+", 0x7), ("a", 0x4),
+    ]),
+    ("blinky", &[
+        ("dpup", 0x3), ("dpdown", 0x6), ("dpleft", 0x7), ("dpright", 0x8),
+        ("lefty-", 0x3), ("lefty+", 0x6), ("leftx-", 0x7), ("leftx+", 0x8),
+    ]),
+    ("missile", &[
+        ("a", 0x8), ("b", 0x8),
+    ]),
+];
+
+/// Mapping of game controller inputs to keys on the original CHIP-8 keypad.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GamepadMap {
+    pub name: String,
+    inputs: HashMap<GamepadInput, u8>,
+}
+
+impl GamepadMap {
+    pub fn preset(name: &str) -> Option<Self> {
+        PRESETS.iter()
+            .find(|(preset_name, _)| *preset_name == name)
+            .map(|(name, inputs)| GamepadMap {
+                name: (*name).to_owned(),
+                inputs: inputs.iter()
+                    .filter_map(|&(input_name, chip8_keycode)| parse_input_name(input_name).map(|input| (input, chip8_keycode)))
+                    .collect(),
+            })
+    }
+
+    pub fn preset_names() -> Vec<&'static str> {
+        PRESETS.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// Build a mapping from input names (e.g. `a`, `dpup` or `leftx-`) mapped to CHIP-8
+    /// hex digits, on top of an optional base mapping. An input name mapped to an empty
+    /// string removes that input from the base mapping.
+    pub fn from_names(name: &str, base: Option<&GamepadMap>, input_names: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut inputs = base.map(|base| base.inputs.clone()).unwrap_or_default();
+
+        for (input_name, hex_digit) in input_names.iter() {
+            let input = parse_input_name(&input_name.to_lowercase())
+                .ok_or_else(|| format!("Gamepad mapping '{}' has unknown input '{}'.", name, input_name))?;
+
+            if hex_digit.is_empty() {
+                inputs.remove(&input);
+                continue;
+            }
+
+            let chip8_keycode = u8::from_str_radix(hex_digit, 16).ok()
+                .filter(|&value| value <= 0xF)
+                .ok_or_else(|| format!("Gamepad mapping '{}' maps input '{}' to '{}', expected a hex digit 0-F.", name, input_name, hex_digit))?;
+
+            inputs.insert(input, chip8_keycode);
+        }
+
+        Ok(GamepadMap { name: name.to_owned(), inputs })
+    }
+
+    /// Resolve a gamepad mapping argument, which is either a name of a preset or a name
+    /// of a custom mapping.
+    pub fn parse(value: &str, custom_maps: &[GamepadMap]) -> Result<Self, String> {
+        Self::preset(value)
+            .or_else(|| custom_maps.iter().find(|map| map.name == value).cloned())
+            .ok_or_else(|| format!("Unknown gamepad mapping '{}', expected one of: {}.", value, Self::preset_names().join(", ")))
+    }
+
+    pub fn chip8_keycode(&self, input: GamepadInput) -> Option<u8> {
+        self.inputs.get(&input).copied()
+    }
+}
+
+fn parse_input_name(name: &str) -> Option<GamepadInput> {
+    INPUT_NAMES.iter()
+        .find(|(input_name, _)| *input_name == name)
+        .map(|&(_, input)| input)
+}
+
+/// Change of controller inputs, which holds down or releases CHIP-8 keys mapped to them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadChange {
+    Pressed { instance_id: u32, input: GamepadInput, chip8_keycode: u8 },
+    Released { instance_id: u32, input: GamepadInput },
+    // All inputs of the controller are released.
+    Disconnected { instance_id: u32 },
+}
+
+/// Game controllers which are currently connected. SDL reports controllers connected
+/// at startup as added too, so all of them are opened through events.
+pub struct Gamepads {
+    subsystem: GameControllerSubsystem,
+    map: GamepadMap,
+    controllers: Vec<GameController>,
+}
+
+impl Gamepads {
+    pub fn new(sdl: &Sdl, map: GamepadMap) -> Result<Self, String> {
+        let subsystem = sdl.game_controller()?;
+
+        debug!("Initialised gamepads: mapping={}", map.name);
+
+        Ok(Gamepads {
+            subsystem,
+            map,
+            controllers: Vec::new(),
+        })
+    }
+
+    /// Handle a controller event, and return inputs which went down or up.
+    pub fn handle_event(&mut self, event: &Event) -> Vec<GamepadChange> {
+        match *event {
+            Event::ControllerDeviceAdded { which: joystick_index, .. } => {
+                match self.subsystem.open(joystick_index) {
+                    Ok(controller) => {
+                        info!("Connected gamepad: {}", controller.name());
+                        self.controllers.push(controller);
+                    },
+                    Err(e) => warn!("Cannot open gamepad {}: {}", joystick_index, e),
+                }
+                Vec::new()
+            },
+            Event::ControllerDeviceRemoved { which: instance_id, .. } => {
+                self.controllers.retain(|controller| {
+                    let is_removed = controller.instance_id() == instance_id;
+                    if is_removed {
+                        info!("Disconnected gamepad: {}", controller.name());
+                    }
+                    !is_removed
+                });
+                vec![GamepadChange::Disconnected { instance_id }]
+            },
+            Event::ControllerButtonDown { which, button, .. } => {
+                self.set_input(which, GamepadInput::Button(button), true).into_iter().collect()
+            },
+            Event::ControllerButtonUp { which, button, .. } => {
+                self.set_input(which, GamepadInput::Button(button), false).into_iter().collect()
+            },
+            Event::ControllerAxisMotion { which, axis, value, .. } => {
+                let negative = self.set_input(which, GamepadInput::AxisNegative(axis), value <= -AXIS_THRESHOLD);
+                let positive = self.set_input(which, GamepadInput::AxisPositive(axis), value >= AXIS_THRESHOLD);
+                negative.into_iter().chain(positive).collect()
+            },
+            _ => Vec::new(),
+        }
+    }
+
+    // Inputs which are not mapped to a CHIP-8 key are left out. Releases are reported
+    // whether or not the input was held down.
+    fn set_input(&self, instance_id: u32, input: GamepadInput, is_active: bool) -> Option<GamepadChange> {
+        if is_active {
+            let chip8_keycode = self.map.chip8_keycode(input)?;
+            Some(GamepadChange::Pressed { instance_id, input, chip8_keycode })
+        } else {
+            Some(GamepadChange::Released { instance_id, input })
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./gamepad_test.rs"]
+mod gamepad_test;
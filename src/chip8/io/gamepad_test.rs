@@ -0,0 +1,57 @@
+#![cfg(test)]
+use speculate::speculate;
+use maplit::btreemap;
+
+use super::*;
+
+speculate! {
+    describe "preset" {
+        test "map only known inputs" {
+            for name in GamepadMap::preset_names() {
+                let preset = PRESETS.iter().find(|(preset_name, _)| *preset_name == name).unwrap();
+                let map = GamepadMap::preset(name).unwrap();
+
+                assert_eq!(map.inputs.len(), preset.1.len(), "gamepad mapping {}", name);
+            }
+        }
+
+        test "map paddles of both players for pong" {
+            let map = GamepadMap::preset("pong").unwrap();
+
+            assert_eq!(map.chip8_keycode(GamepadInput::Button(Button::DPadDown)), Some(0x4));
+            assert_eq!(map.chip8_keycode(GamepadInput::AxisNegative(Axis::RightY)), Some(0xC));
+        }
+    }
+
+    describe "from_names" {
+        test "override inputs of the base mapping" {
+            let default = GamepadMap::preset(GAMEPAD_MAP_DEFAULT).unwrap();
+            let input_names = btreemap!{
+                "B".to_owned() => "".to_owned(),
+                "rightshoulder".to_owned() => "f".to_owned(),
+            };
+
+            let map = GamepadMap::from_names("custom", Some(&default), &input_names).unwrap();
+
+            assert_eq!(map.chip8_keycode(GamepadInput::Button(Button::B)), None);
+            assert_eq!(map.chip8_keycode(GamepadInput::Button(Button::RightShoulder)), Some(0xF));
+            assert_eq!(map.chip8_keycode(GamepadInput::Button(Button::A)), Some(0x5));
+        }
+
+        test "reject unknown inputs" {
+            let input_names = btreemap!{ "z".to_owned() => "1".to_owned() };
+
+            assert!(GamepadMap::from_names("custom", None, &input_names).is_err());
+        }
+    }
+
+    describe "parse" {
+        test "find presets and custom mappings by name" {
+            let custom = GamepadMap::from_names("custom", None, &BTreeMap::new()).unwrap();
+
+            assert_eq!(GamepadMap::parse("tetris", &[]).unwrap().name, "tetris");
+            assert_eq!(GamepadMap::parse("custom", &[custom]).unwrap().name, "custom");
+            assert!(GamepadMap::parse("unknown", &[]).is_err());
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+use crate::chip8::interface::KeyEvent;
+
+/// Turns presses and releases of inputs (keyboard keys, gamepad buttons) into presses
+/// and releases of CHIP-8 keys. Several inputs can be mapped to the same CHIP-8 key, which
+/// goes up only once all of them are released.
+pub struct HeldKeys<I> {
+    held: HashMap<I, u8>,
+}
+
+impl<I: Hash + Eq> HeldKeys<I> {
+    pub fn new() -> Self {
+        HeldKeys {
+            held: HashMap::new(),
+        }
+    }
+
+    pub fn press(&mut self, input: I, keycode: u8) -> Option<KeyEvent> {
+        if self.held.contains_key(&input) {
+            return None;
+        }
+
+        let is_held = self.is_held(keycode);
+        self.held.insert(input, keycode);
+
+        if is_held { None } else { Some(KeyEvent::pressed(keycode)) }
+    }
+
+    pub fn release(&mut self, input: &I) -> Option<KeyEvent> {
+        let keycode = self.held.remove(input)?;
+
+        if self.is_held(keycode) { None } else { Some(KeyEvent::released(keycode)) }
+    }
+
+    /// Release inputs which match the predicate.
+    pub fn release_where<F: Fn(&I) -> bool>(&mut self, predicate: F) -> Vec<KeyEvent> {
+        let keycodes: BTreeSet<u8> = self.held.iter()
+            .filter(|(input, _)| predicate(input))
+            .map(|(_, &keycode)| keycode)
+            .collect();
+
+        self.held.retain(|input, _| !predicate(input));
+
+        keycodes.into_iter()
+            .filter(|&keycode| !self.is_held(keycode))
+            .map(KeyEvent::released)
+            .collect()
+    }
+
+    pub fn release_all(&mut self) -> Vec<KeyEvent> {
+        self.release_where(|_| true)
+    }
+
+    fn is_held(&self, keycode: u8) -> bool {
+        self.held.values().any(|&held_keycode| held_keycode == keycode)
+    }
+}
+
+#[cfg(test)]
+#[path = "./held_keys_test.rs"]
+mod held_keys_test;
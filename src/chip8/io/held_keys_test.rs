@@ -0,0 +1,41 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "press" {
+        test "press a CHIP-8 key only when the first of its inputs is pressed" {
+            let mut held_keys = HeldKeys::new();
+
+            assert_eq!(held_keys.press('w', 0x5), Some(KeyEvent::pressed(0x5)));
+            assert_eq!(held_keys.press('w', 0x5), None);
+            assert_eq!(held_keys.press('i', 0x5), None);
+        }
+    }
+
+    describe "release" {
+        test "release a CHIP-8 key only when all of its inputs are released" {
+            let mut held_keys = HeldKeys::new();
+            held_keys.press('w', 0x5);
+            held_keys.press('i', 0x5);
+
+            assert_eq!(held_keys.release(&'w'), None);
+            assert_eq!(held_keys.release(&'i'), Some(KeyEvent::released(0x5)));
+            assert_eq!(held_keys.release(&'i'), None);
+        }
+    }
+
+    describe "release_where" {
+        test "release matching inputs" {
+            let mut held_keys = HeldKeys::new();
+            held_keys.press((0, 'a'), 0x5);
+            held_keys.press((0, 'b'), 0x6);
+            held_keys.press((1, 'b'), 0x6);
+
+            let events = held_keys.release_where(|&(controller, _)| controller == 0);
+
+            assert_eq!(events, [KeyEvent::released(0x5)]);
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, HashMap};
+use sdl2::keyboard::Keycode;
+
+pub const KEYMAP_DEFAULT: &str = "qwerty";
+
+// CHIP-8 can detect input from a sixteen key keypad, where each key corresponds to a
+// hexadecimal digit. Original computers running CHIP-8 provided such a keypad. For
+// modern keyboards, we need to map keys to simulate usage of the original keypad.
+//
+// Presets map the same physical keys on each keyboard layout, e.g. for QWERTY:
+// ```
+// QWERTY        |    Original
+// ------------------------------------
+// 1  2  3  4    |    1  2  3  C
+// Q  W  E  R    |    4  5  6  D
+// A  S  D  F    |    7  8  9  E
+// Z  X  C  V    |    A  0  B  F
+// ```
+pub const KEYPAD_LAYOUT: [u8; 16] = [
+    0x1, 0x2, 0x3, 0xC,
+    0x4, 0x5, 0x6, 0xD,
+    0x7, 0x8, 0x9, 0xE,
+    0xA, 0x0, 0xB, 0xF,
+];
+
+// Characters of keys in the same order as `KEYPAD_LAYOUT`.
+const PRESETS: [(&str, &str); 4] = [
+    ("qwerty", "1234qwerasdfzxcv"),
+    ("azerty", "1234azerqsdfwxcv"),
+    ("qwertz", "1234qwerasdfyxcv"),
+    ("dvorak", "1234',.paoeu;qjk"),
+];
+
+/// Mapping of keys on the modern keyboard to keys on the original CHIP-8 keypad.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    pub name: String,
+    keys: HashMap<Keycode, u8>,
+}
+
+impl Keymap {
+    pub fn preset(name: &str) -> Option<Self> {
+        PRESETS.iter()
+            .find(|(preset_name, _)| *preset_name == name)
+            .map(|(name, chars)| Keymap {
+                name: (*name).to_owned(),
+                keys: chars.chars()
+                    .filter_map(char_keycode)
+                    .zip(KEYPAD_LAYOUT.iter().copied())
+                    .collect(),
+            })
+    }
+
+    pub fn preset_names() -> Vec<&'static str> {
+        PRESETS.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// Build a keymap from key names (e.g. `Q`, `Up` or `Keypad 5`) mapped to CHIP-8
+    /// hex digits, on top of an optional base keymap. A key name mapped to an empty
+    /// string removes that key from the base keymap.
+    pub fn from_names(name: &str, base: Option<&Keymap>, key_names: &BTreeMap<String, String>) -> Result<Self, String> {
+        let mut keys = base.map(|base| base.keys.clone()).unwrap_or_default();
+
+        for (key_name, hex_digit) in key_names.iter() {
+            let keycode = parse_key_name(key_name)
+                .ok_or_else(|| format!("Keymap '{}' has unknown key '{}'.", name, key_name))?;
+
+            if hex_digit.is_empty() {
+                keys.remove(&keycode);
+                continue;
+            }
+
+            let chip8_keycode = u8::from_str_radix(hex_digit, 16).ok()
+                .filter(|&value| value <= 0xF)
+                .ok_or_else(|| format!("Keymap '{}' maps key '{}' to '{}', expected a hex digit 0-F.", name, key_name, hex_digit))?;
+
+            keys.insert(keycode, chip8_keycode);
+        }
+
+        Ok(Keymap { name: name.to_owned(), keys })
+    }
+
+    /// Resolve a keymap argument, which is either a name of a preset or a name of a
+    /// custom keymap.
+    pub fn parse(value: &str, custom_keymaps: &[Keymap]) -> Result<Self, String> {
+        Self::preset(value)
+            .or_else(|| custom_keymaps.iter().find(|keymap| keymap.name == value).cloned())
+            .ok_or_else(|| format!("Unknown keymap '{}', expected one of: {}.", value, Self::preset_names().join(", ")))
+    }
+
+    pub fn chip8_keycode(&self, keycode: Keycode) -> Option<u8> {
+        self.keys.get(&keycode).copied()
+    }
+
+    /// Keys which are mapped to the CHIP-8 key, in a stable order.
+    pub fn keycodes(&self, chip8_keycode: u8) -> Vec<Keycode> {
+        let mut keycodes: Vec<Keycode> = self.keys.iter()
+            .filter(|(_, &mapped)| mapped == chip8_keycode)
+            .map(|(&keycode, _)| keycode)
+            .collect();
+        keycodes.sort_by_key(|&keycode| keycode as i32);
+        keycodes
+    }
+}
+
+/// SDL keycodes of printable keys are the (lowercase) characters they produce.
+pub fn char_keycode(c: char) -> Option<Keycode> {
+    Keycode::from_i32(c.to_ascii_lowercase() as i32)
+}
+
+fn parse_key_name(name: &str) -> Option<Keycode> {
+    let mut chars = name.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => char_keycode(c),
+        _               => Keycode::from_name(name),
+    }
+}
+
+#[cfg(test)]
+#[path = "./keymap_test.rs"]
+mod keymap_test;
@@ -0,0 +1,76 @@
+#![cfg(test)]
+use speculate::speculate;
+use maplit::btreemap;
+
+use super::*;
+
+speculate! {
+    describe "preset" {
+        test "map all 16 CHIP-8 keys" {
+            for name in Keymap::preset_names() {
+                let keymap = Keymap::preset(name).unwrap();
+
+                assert_eq!(keymap.keys.len(), 16, "keymap {}", name);
+            }
+        }
+
+        test "map the same physical keys on each layout" {
+            let qwerty = Keymap::preset("qwerty").unwrap();
+            let azerty = Keymap::preset("azerty").unwrap();
+            let dvorak = Keymap::preset("dvorak").unwrap();
+
+            assert_eq!(qwerty.chip8_keycode(Keycode::Q), Some(0x4));
+            assert_eq!(azerty.chip8_keycode(Keycode::A), Some(0x4));
+            assert_eq!(azerty.chip8_keycode(Keycode::Q), Some(0x7));
+            assert_eq!(dvorak.chip8_keycode(Keycode::Quote), Some(0x4));
+            assert_eq!(dvorak.chip8_keycode(Keycode::K), Some(0xF));
+        }
+    }
+
+    describe "keycodes" {
+        test "find all keys mapped to a CHIP-8 key" {
+            let qwerty = Keymap::preset("qwerty").unwrap();
+            let key_names = btreemap!{ "p".to_owned() => "5".to_owned() };
+
+            let keymap = Keymap::from_names("custom", Some(&qwerty), &key_names).unwrap();
+
+            assert_eq!(keymap.keycodes(0x5), [Keycode::P, Keycode::W]);
+            assert_eq!(keymap.keycodes(0x1), [Keycode::Num1]);
+        }
+    }
+
+    describe "from_names" {
+        test "override keys of the base keymap" {
+            let qwerty = Keymap::preset("qwerty").unwrap();
+            let key_names = btreemap!{
+                "q".to_owned() => "".to_owned(),
+                "p".to_owned() => "c".to_owned(),
+            };
+
+            let keymap = Keymap::from_names("custom", Some(&qwerty), &key_names).unwrap();
+
+            assert_eq!(keymap.chip8_keycode(Keycode::Q), None);
+            assert_eq!(keymap.chip8_keycode(Keycode::P), Some(0xC));
+            assert_eq!(keymap.chip8_keycode(Keycode::W), Some(0x5));
+        }
+
+        test "reject values which are not a single hex digit" {
+            let key_names = btreemap!{ "p".to_owned() => "10".to_owned() };
+
+            assert!(Keymap::from_names("custom", None, &key_names).is_err());
+        }
+    }
+
+    describe "parse" {
+        test "find presets and custom keymaps by name" {
+            let custom = Keymap::from_names("custom", None, &BTreeMap::new()).unwrap();
+
+            assert_eq!(Keymap::parse("dvorak", &[]).unwrap().name, "dvorak");
+            assert_eq!(Keymap::parse("custom", &[custom]).unwrap().name, "custom");
+        }
+
+        test "reject unknown keymaps" {
+            assert!(Keymap::parse("colemak", &[]).is_err());
+        }
+    }
+}
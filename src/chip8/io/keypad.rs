@@ -1,84 +1,177 @@
-use lazy_static::lazy_static;
-use maplit::hashmap;
-use std::collections::{HashMap, HashSet, BTreeSet};
+use std::collections::VecDeque;
+use std::mem;
 use sdl2::Sdl;
-use sdl2::keyboard::Keycode;
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::MouseButton;
 use sdl2::EventPump;
+use crate::chip8::interface::KeyEvent;
+use super::frontend::Controls;
+use super::gamepad::{GamepadChange, GamepadInput, GamepadMap, Gamepads};
+use super::held_keys::HeldKeys;
+use super::keymap::Keymap;
 
-type Chip8Keycode = u8;
+/// Keys which control the interpreter itself, rather than the CHIP-8 program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hotkey {
+    CyclePalette,
+    ToggleFullscreen,
+    Screenshot,
+    ToggleRecording,
+    ToggleKeypadOverlay,
+    ToggleControlsHelp,
+    ToggleMute,
+    TogglePause,
+    AdvanceFrame,
+    ToggleSlowMotion,
+    // Fast-forward while the key is held down.
+    FastForward { is_held: bool },
+    Reset,
+    // Stop the program and pick another one in the launcher.
+    OpenLauncher,
+}
+
+impl Hotkey {
+    fn from_key(keycode: Keycode, keymod: Mod) -> Option<Self> {
+        let is_alt_pressed = keymod.intersects(Mod::LALTMOD | Mod::RALTMOD);
 
-// CHIP-8 can detect input from a sixteen key keypad, where each key corresponds to a
-// hexadecimal digit. Original computers running CHIP-8 provided such a keypad. For
-// modern keyboards, we need to map keys to simulate usage of the original keypad.
-// 
-// # Mapping
-// ```
-// QWERTY        |    Original
-// ------------------------------------
-// 1  2  3  4    |    1  2  3  C
-// Q  W  E  R    |    4  5  6  D
-// A  S  D  F    |    7  8  9  E
-// Z  X  C  V    |    A  0  B  F
-// ```
-lazy_static! {
-    static ref QWERTY_TO_CHIP8_KEYCODE: HashMap<Keycode, Chip8Keycode> = hashmap!{
-        Keycode::Num1 => 0x1,
-        Keycode::Num2 => 0x2,
-        Keycode::Num3 => 0x3,
-        Keycode::Num4 => 0xC,
-    
-        Keycode::Q => 0x4,
-        Keycode::W => 0x5,
-        Keycode::E => 0x6,
-        Keycode::R => 0xD,
-    
-        Keycode::A => 0x7,
-        Keycode::S => 0x8,
-        Keycode::D => 0x9,
-        Keycode::F => 0xE,
-    
-        Keycode::Z => 0xA,
-        Keycode::X => 0x0,
-        Keycode::C => 0xB,
-        Keycode::V => 0xF,
-    };
+        match keycode {
+            Keycode::F1                      => Some(Hotkey::ToggleControlsHelp),
+            Keycode::F2                      => Some(Hotkey::CyclePalette),
+            Keycode::F3                      => Some(Hotkey::ToggleMute),
+            Keycode::F4                      => Some(Hotkey::ToggleKeypadOverlay),
+            Keycode::F5                      => Some(Hotkey::TogglePause),
+            Keycode::F6                      => Some(Hotkey::AdvanceFrame),
+            Keycode::F7                      => Some(Hotkey::ToggleSlowMotion),
+            Keycode::F8                      => Some(Hotkey::Reset),
+            Keycode::F9                      => Some(Hotkey::OpenLauncher),
+            Keycode::Tab                     => Some(Hotkey::FastForward { is_held: true }),
+            Keycode::F10                     => Some(Hotkey::ToggleRecording),
+            Keycode::F11                     => Some(Hotkey::ToggleFullscreen),
+            Keycode::F12                     => Some(Hotkey::Screenshot),
+            Keycode::Return if is_alt_pressed => Some(Hotkey::ToggleFullscreen),
+            _                                => None,
+        }
+    }
 }
 
 pub enum KeypadState {
     PressedEscape,
-    PressedKeycodes(BTreeSet<Chip8Keycode>)
+    Hotkey(Hotkey),
+    // Window was resized or uncovered, and its contents need to be drawn again.
+    WindowChanged,
+    // Left mouse button (or touch) went down or up at a point in the window.
+    Pointer { x: i32, y: i32, is_pressed: bool },
+    // CHIP-8 keys which went down or up since the previous state, in order.
+    KeyEvents(Vec<KeyEvent>),
+}
+
+// Inputs which hold CHIP-8 keys down. They share the bookkeeping, so that a key held by
+// one of them stays down when another one which is mapped to it is released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum HeldInput {
+    Key(Keycode),
+    Gamepad(u32, GamepadInput),
+    Pointer,
 }
 
 pub struct Keypad {
-    events: EventPump
+    events: EventPump,
+    keymap: Keymap,
+    gamepads: Gamepads,
+    held_keys: HeldKeys<HeldInput>,
+    pending_states: VecDeque<KeypadState>,
+    key_events: Vec<KeyEvent>,
 }
 
 impl Keypad {
-    pub fn new(sdl: &Sdl) -> Result<Self, String> {
+    pub fn new(sdl: &Sdl, keymap: Keymap, gamepad_map: GamepadMap) -> Result<Self, String> {
         let events = sdl.event_pump()?;
-        
-        Ok(Keypad { events })
-    }
+        let gamepads = Gamepads::new(sdl, gamepad_map)?;
 
-    pub fn state(&mut self) -> KeypadState {
-        self.events.pump_events();
+        debug!("Initialised keypad: keymap={}", keymap.name);
 
-        let pressed_keycodes: HashSet<Keycode> = self.events
-            .keyboard_state()
-            .pressed_scancodes()
-            .filter_map(Keycode::from_scancode)
-            .collect();
+        Ok(Keypad {
+            events,
+            keymap,
+            gamepads,
+            held_keys: HeldKeys::new(),
+            pending_states: VecDeque::new(),
+            key_events: Vec::new(),
+        })
+    }
+}
+
+impl Controls for Keypad {
+    fn state(&mut self) -> KeypadState {
+        for event in self.events.poll_iter() {
+            match event {
+                Event::Quit { .. } => return KeypadState::PressedEscape,
+                Event::KeyDown { keycode: Some(Keycode::Escape), .. } => return KeypadState::PressedEscape,
+                Event::KeyDown { keycode: Some(keycode), keymod, repeat: false, .. } => {
+                    if let Some(hotkey) = Hotkey::from_key(keycode, keymod) {
+                        self.pending_states.push_back(KeypadState::Hotkey(hotkey));
+                    } else if let Some(chip8_keycode) = self.keymap.chip8_keycode(keycode) {
+                        self.key_events.extend(self.held_keys.press(HeldInput::Key(keycode), chip8_keycode));
+                    }
+                },
+                Event::KeyUp { keycode: Some(Keycode::Tab), .. } => {
+                    self.pending_states.push_back(KeypadState::Hotkey(Hotkey::FastForward { is_held: false }));
+                },
+                Event::KeyUp { keycode: Some(keycode), .. } => {
+                    self.key_events.extend(self.held_keys.release(&HeldInput::Key(keycode)));
+                },
+                // Releases of keys, buttons and the mouse are not reported to a window without focus.
+                Event::Window { win_event: WindowEvent::FocusLost, .. } => {
+                    self.key_events.extend(self.held_keys.release_all());
+                    self.pending_states.push_back(KeypadState::Hotkey(Hotkey::FastForward { is_held: false }));
+                },
+                Event::Window { win_event: WindowEvent::SizeChanged(..), .. }
+                | Event::Window { win_event: WindowEvent::Exposed, .. } => {
+                    self.pending_states.push_back(KeypadState::WindowChanged);
+                },
+                Event::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } => {
+                    self.pending_states.push_back(KeypadState::Pointer { x, y, is_pressed: true });
+                },
+                Event::MouseButtonUp { mouse_btn: MouseButton::Left, x, y, .. } => {
+                    self.pending_states.push_back(KeypadState::Pointer { x, y, is_pressed: false });
+                },
+                Event::ControllerDeviceAdded { .. }
+                | Event::ControllerDeviceRemoved { .. }
+                | Event::ControllerButtonDown { .. }
+                | Event::ControllerButtonUp { .. }
+                | Event::ControllerAxisMotion { .. } => {
+                    for change in self.gamepads.handle_event(&event) {
+                        self.key_events.extend(change_gamepad_input(&mut self.held_keys, change));
+                    }
+                },
+                _ => {},
+            }
+        }
 
-        if pressed_keycodes.contains(&Keycode::Escape) {
-            KeypadState::PressedEscape
-        } else {
-            let pressed_chip8_keycodes: BTreeSet<Chip8Keycode> = pressed_keycodes
-                .iter()
-                .filter_map(|keycode| QWERTY_TO_CHIP8_KEYCODE.get(&keycode))
-                .cloned()
-                .collect();
+        self.pending_states.pop_front()
+            .unwrap_or_else(|| KeypadState::KeyEvents(mem::take(&mut self.key_events)))
+    }
 
-            KeypadState::PressedKeycodes(pressed_chip8_keycodes)
+    fn set_pointer_keycode(&mut self, chip8_keycode: Option<u8>) -> Vec<KeyEvent> {
+        let mut key_events: Vec<KeyEvent> = self.held_keys.release(&HeldInput::Pointer).into_iter().collect();
+        if let Some(chip8_keycode) = chip8_keycode {
+            key_events.extend(self.held_keys.press(HeldInput::Pointer, chip8_keycode));
         }
+        key_events
+    }
+}
+
+fn change_gamepad_input(held_keys: &mut HeldKeys<HeldInput>, change: GamepadChange) -> Vec<KeyEvent> {
+    match change {
+        GamepadChange::Pressed { instance_id, input, chip8_keycode } => {
+            held_keys.press(HeldInput::Gamepad(instance_id, input), chip8_keycode).into_iter().collect()
+        },
+        GamepadChange::Released { instance_id, input } => {
+            held_keys.release(&HeldInput::Gamepad(instance_id, input)).into_iter().collect()
+        },
+        GamepadChange::Disconnected { instance_id } => {
+            held_keys.release_where(|input| matches!(input, HeldInput::Gamepad(id, _) if *id == instance_id))
+        },
     }
 }
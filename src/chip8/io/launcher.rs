@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use std::panic::{self, AssertUnwindSafe};
+use crate::args::ProgramArg;
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+use crate::chip8::cpu::Processor;
+use crate::chip8::interface::Input;
+use crate::chip8::platform::Platform;
+
+// Title screens are taken after the program runs for this long without input, which is
+// enough for most programs to draw them.
+const TITLE_SCREEN_FRAMES: u32 = 120;
+
+/// Program which can be picked in the launcher.
+pub struct LauncherEntry {
+    pub program: ProgramArg,
+    pub title: String,
+    pub description: String,
+    /// Notes about the program from the config file, e.g. which keys it uses.
+    pub notes: Option<String>,
+    pub rom: Vec<u8>,
+    pub speed: u32,
+}
+
+/// Menu of programs to pick from when the interpreter starts without a program, with
+/// the selected one and thumbnails of title screens taken so far.
+pub struct Launcher {
+    entries: Vec<LauncherEntry>,
+    selected: usize,
+    // First entry which is visible, when there are more than fit into the window.
+    scroll: usize,
+    thumbnails: HashMap<usize, [[bool; CHIP8_WIDTH]; CHIP8_HEIGHT]>,
+}
+
+impl Launcher {
+    pub fn new(entries: Vec<LauncherEntry>) -> Self {
+        Launcher {
+            entries,
+            selected: 0,
+            scroll: 0,
+            thumbnails: HashMap::new(),
+        }
+    }
+
+    pub fn entries(&self) -> &[LauncherEntry] {
+        &self.entries
+    }
+
+    pub fn selected(&self) -> Option<&LauncherEntry> {
+        self.entries.get(self.selected)
+    }
+
+    pub fn selected_index(&self) -> usize {
+        self.selected
+    }
+
+    /// Move the selection by a number of entries, stopping at the first and last one.
+    pub fn move_selection(&mut self, offset: isize) {
+        let last = self.entries.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize + offset).max(0).min(last) as usize;
+    }
+
+    /// Select the entry of the program, e.g. the one which ran last.
+    pub fn select_program(&mut self, program: &ProgramArg) {
+        if let Some(index) = self.entries.iter().position(|entry| entry.program == *program) {
+            self.selected = index;
+        }
+    }
+
+    /// Indices of the entries which fit into the number of rows, scrolled so that the
+    /// selected entry is among them.
+    pub fn visible_entries(&mut self, rows: usize) -> std::ops::Range<usize> {
+        let rows = rows.max(1);
+
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + rows {
+            self.scroll = self.selected + 1 - rows;
+        }
+
+        self.scroll..self.entries.len().min(self.scroll + rows)
+    }
+
+    /// Title screen of the selected program, which is run headless the first time.
+    pub fn thumbnail(&mut self) -> Option<&[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT]> {
+        let entry = self.entries.get(self.selected)?;
+
+        let thumbnail = self.thumbnails.entry(self.selected)
+            .or_insert_with(|| title_screen(&entry.rom, entry.speed).unwrap_or_else(|| {
+                warn!("Cannot take the title screen of program {}.", entry.title);
+                [[false; CHIP8_WIDTH]; CHIP8_HEIGHT]
+            }));
+
+        Some(thumbnail)
+    }
+}
+
+/// Run the program without input and without a frontend for a couple of seconds, and
+/// return what it draws. Programs which do not fit into CHIP-8 memory or which crash the
+/// interpreter have no title screen.
+pub fn title_screen(rom: &[u8], instructions_per_frame: u32) -> Option<[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT]> {
+    if rom.is_empty() || rom.len() > Platform::Chip8.program_size_max() {
+        return None;
+    }
+
+    let run = || {
+        let mut processor = Processor::new();
+        processor.load_program(rom);
+
+        let mut pixels = [[false; CHIP8_WIDTH]; CHIP8_HEIGHT];
+        for _ in 0..TITLE_SCREEN_FRAMES {
+            for _ in 0..instructions_per_frame {
+                pixels = processor.execute_instruction(Input { key_events: &[] }).pixels;
+            }
+            processor.update_timers();
+        }
+        pixels
+    };
+
+    panic::catch_unwind(AssertUnwindSafe(run)).ok()
+}
+
+#[cfg(test)]
+#[path = "./launcher_test.rs"]
+mod launcher_test;
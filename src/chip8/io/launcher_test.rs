@@ -0,0 +1,71 @@
+#![cfg(test)]
+use speculate::speculate;
+use std::env::current_dir;
+use crate::chip8::io::filesystem::Filesystem;
+
+use super::*;
+
+fn build_launcher(count: usize) -> Launcher {
+    let entries = (0..count)
+        .map(|index| LauncherEntry {
+            program: ProgramArg::ProgramFile(format!("{}.ch8", index)),
+            title: index.to_string(),
+            description: String::new(),
+            notes: None,
+            rom: Vec::new(),
+            speed: 9,
+        })
+        .collect();
+
+    Launcher::new(entries)
+}
+
+speculate! {
+    describe "selection" {
+        test "move the selection and stop at the first and last entry" {
+            let mut launcher = build_launcher(5);
+
+            launcher.move_selection(3);
+            assert_eq!(launcher.selected_index(), 3);
+            launcher.move_selection(10);
+            assert_eq!(launcher.selected_index(), 4);
+            launcher.move_selection(-10);
+            assert_eq!(launcher.selected_index(), 0);
+        }
+
+        test "select the entry of a program" {
+            let mut launcher = build_launcher(5);
+
+            launcher.select_program(&ProgramArg::ProgramFile("2.ch8".to_owned()));
+
+            assert_eq!(launcher.selected().unwrap().title, "2");
+        }
+
+        test "scroll the visible entries to the selection" {
+            let mut launcher = build_launcher(10);
+            assert_eq!(launcher.visible_entries(4), 0..4);
+
+            launcher.move_selection(5);
+            assert_eq!(launcher.visible_entries(4), 2..6);
+
+            launcher.move_selection(-4);
+            assert_eq!(launcher.visible_entries(4), 1..5);
+            assert_eq!(launcher.visible_entries(20), 1..10);
+        }
+    }
+
+    describe "title_screen" {
+        test "draw what the program draws without input" {
+            let program = Filesystem::new(current_dir().unwrap()).load_built_in_program("maze").unwrap();
+
+            let pixels = title_screen(&program.rom, 9).unwrap();
+
+            assert!(pixels.iter().flatten().any(|&pixel| pixel));
+        }
+
+        test "skip programs which do not fit into memory" {
+            assert!(title_screen(&[], 9).is_none());
+            assert!(title_screen(&[0x12; 0x1000], 9).is_none());
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use sdl2::{EventPump, Sdl};
+use sdl2::event::{Event, WindowEvent};
+use sdl2::gfx::primitives::DrawRenderer;
+use sdl2::keyboard::Keycode;
+use sdl2::rect::Rect;
+use sdl2::render::Canvas;
+use sdl2::video::Window;
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT, WINDOW_TITLE};
+use crate::util::text::{wrap_text, FONT_SIZE, LINE_HEIGHT};
+use super::launcher::Launcher;
+use super::palette::Palette;
+
+const MARGIN: i32 = 8;
+// Smallest window height which the launcher is drawn into at 1x, larger windows scale it
+// up by whole numbers so that text stays readable.
+const BASE_HEIGHT: u32 = 240;
+const PAGE_ROWS: isize = 10;
+
+const HELP: &str = "Up/Down: select  Enter: run  Esc: quit";
+
+/// Window which shows the launcher, until a program is picked from it.
+pub struct LauncherWindow {
+    canvas: Canvas<Window>,
+    events: EventPump,
+    palette: Palette,
+}
+
+impl LauncherWindow {
+    pub fn new(sdl: &Sdl, size: (u32, u32), palette: Palette) -> Result<Self, String> {
+        let (width, height) = size;
+
+        let video = sdl.video()?;
+        let window = video
+            .window(WINDOW_TITLE, width, height)
+            .position_centered()
+            .resizable()
+            .opengl()
+            .build()
+            .map_err(|e| e.to_string())?;
+        let canvas = window
+            .into_canvas()
+            .build()
+            .map_err(|e| e.to_string())?;
+        let events = sdl.event_pump()?;
+
+        Ok(LauncherWindow { canvas, events, palette })
+    }
+
+    /// Wait for a program to be picked, and return the index of its entry. Returns
+    /// nothing when the window is closed instead.
+    pub fn pick(&mut self, launcher: &mut Launcher) -> Result<Option<usize>, String> {
+        self.render(launcher)?;
+
+        loop {
+            match self.events.wait_event() {
+                Event::Quit { .. } | Event::KeyDown { keycode: Some(Keycode::Escape), .. } => return Ok(None),
+                Event::KeyDown { keycode: Some(keycode), .. } => {
+                    match keycode {
+                        Keycode::Return | Keycode::KpEnter if launcher.selected().is_some() => return Ok(Some(launcher.selected_index())),
+                        Keycode::Up       => launcher.move_selection(-1),
+                        Keycode::Down     => launcher.move_selection(1),
+                        Keycode::PageUp   => launcher.move_selection(-PAGE_ROWS),
+                        Keycode::PageDown => launcher.move_selection(PAGE_ROWS),
+                        Keycode::Home     => launcher.move_selection(-(launcher.entries().len() as isize)),
+                        Keycode::End      => launcher.move_selection(launcher.entries().len() as isize),
+                        _                 => continue,
+                    }
+                    self.render(launcher)?;
+                },
+                Event::Window { win_event: WindowEvent::SizeChanged(..), .. }
+                | Event::Window { win_event: WindowEvent::Exposed, .. } => self.render(launcher)?,
+                _ => {},
+            }
+        }
+    }
+
+    pub fn size(&self) -> (u32, u32) {
+        self.canvas.window().size()
+    }
+
+    // List of programs on the left, and the title screen, description and notes of the
+    // selected program on the right.
+    fn render(&mut self, launcher: &mut Launcher) -> Result<(), String> {
+        let (width, height) = self.canvas.output_size()?;
+        let scale = (height / BASE_HEIGHT).max(1);
+        self.canvas.set_scale(scale as f32, scale as f32)?;
+        let (width, height) = ((width / scale) as i32, (height / scale) as i32);
+
+        let background = self.palette.background();
+        let foreground = self.palette.foreground();
+        self.canvas.set_draw_color(background);
+        self.canvas.clear();
+
+        let list_width = width * 2 / 5;
+        let rows = ((height - 2 * MARGIN - LINE_HEIGHT) / LINE_HEIGHT).max(1) as usize;
+        let columns = ((list_width - MARGIN) / FONT_SIZE).max(1) as usize;
+
+        for (row, index) in launcher.visible_entries(rows).enumerate() {
+            let y = MARGIN + row as i32 * LINE_HEIGHT;
+            let title: String = launcher.entries()[index].title.chars().take(columns).collect();
+
+            let text = if index == launcher.selected_index() {
+                self.canvas.set_draw_color(foreground);
+                self.canvas.fill_rect(Rect::new(MARGIN / 2, y - 2, list_width as u32, LINE_HEIGHT as u32))?;
+                background
+            } else {
+                foreground
+            };
+            self.canvas.string(MARGIN as i16, y as i16, &title, text)?;
+        }
+
+        let left = list_width + MARGIN;
+        let details_width = width - left - MARGIN;
+        let mut y = MARGIN;
+
+        if let Some(thumbnail) = launcher.thumbnail() {
+            let pixel_size = (details_width / CHIP8_WIDTH as i32).min((height / 2) / CHIP8_HEIGHT as i32).max(1);
+            self.render_thumbnail(thumbnail, left, y, pixel_size)?;
+            y += CHIP8_HEIGHT as i32 * pixel_size + LINE_HEIGHT;
+        }
+
+        if let Some(entry) = launcher.selected() {
+            let columns = (details_width / FONT_SIZE).max(1) as usize;
+            let mut lines = wrap_text(&entry.title, columns);
+            lines.extend(wrap_text(&entry.description, columns));
+            if let Some(notes) = &entry.notes {
+                lines.push(String::new());
+                lines.extend(wrap_text(notes, columns));
+            }
+
+            // Lines which do not fit above the help line are left out.
+            let rows = ((height - 2 * LINE_HEIGHT - y) / LINE_HEIGHT).max(0) as usize;
+            for (row, line) in lines.iter().take(rows).enumerate() {
+                self.canvas.string(left as i16, (y + row as i32 * LINE_HEIGHT) as i16, line, foreground)?;
+            }
+        }
+
+        self.canvas.string(MARGIN as i16, (height - MARGIN - FONT_SIZE) as i16, HELP, foreground)?;
+
+        self.canvas.present();
+        Ok(())
+    }
+
+    fn render_thumbnail(&mut self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], left: i32, top: i32, pixel_size: i32) -> Result<(), String> {
+        for (y, row) in pixels.iter().enumerate() {
+            for (x, &pixel) in row.iter().enumerate() {
+                let color = if pixel { self.palette.foreground() } else { self.palette.background() };
+
+                self.canvas.set_draw_color(color);
+                self.canvas.fill_rect(Rect::new(left + x as i32 * pixel_size, top + y as i32 * pixel_size, pixel_size as u32, pixel_size as u32))?;
+            }
+        }
+
+        self.canvas.set_draw_color(self.palette.foreground());
+        self.canvas.draw_rect(Rect::new(left - 1, top - 1, (CHIP8_WIDTH as i32 * pixel_size + 2) as u32, (CHIP8_HEIGHT as i32 * pixel_size + 2) as u32))
+    }
+}
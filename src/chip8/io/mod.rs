@@ -1,4 +1,21 @@
+pub mod cartridge;
+pub mod database;
 pub mod display;
 pub mod filesystem;
+pub mod frontend;
+pub mod gamepad;
+pub mod held_keys;
+pub mod keymap;
 pub mod keypad;
+pub mod launcher;
+pub mod launcher_window;
+pub mod overlay;
+pub mod palette;
+pub mod recorder;
+pub mod ring_buffer;
+pub mod screenshot;
 pub mod sound;
+pub mod terminal;
+pub mod tone;
+pub mod viewport;
+pub mod watcher;
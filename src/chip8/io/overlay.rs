@@ -0,0 +1,142 @@
+use std::collections::{BTreeMap, BTreeSet};
+use sdl2::rect::Rect;
+use super::keymap::{Keymap, KEYPAD_LAYOUT};
+use super::viewport::Viewport;
+
+/// On-screen CHIP-8 keypad drawn over the screen. It shows which keyboard key is mapped
+/// to each CHIP-8 key, highlights pressed keys, and its keys can be clicked or touched.
+pub struct KeypadOverlay {
+    pub is_visible: bool,
+    // Names of keyboard keys, indexed by CHIP-8 key.
+    key_labels: Vec<String>,
+    pressed_keycodes: BTreeSet<u8>,
+}
+
+impl KeypadOverlay {
+    pub fn new(key_labels: Vec<String>, is_visible: bool) -> Self {
+        KeypadOverlay {
+            is_visible,
+            key_labels,
+            pressed_keycodes: BTreeSet::new(),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.is_visible = !self.is_visible;
+
+        debug!("Switched keypad overlay: visible={}", self.is_visible);
+    }
+
+    /// Returns whether pressed keys changed while the overlay is visible, and it needs
+    /// to be drawn again.
+    pub fn set_pressed_keycodes(&mut self, pressed_keycodes: &BTreeSet<u8>) -> bool {
+        if self.pressed_keycodes == *pressed_keycodes {
+            return false;
+        }
+
+        self.pressed_keycodes = pressed_keycodes.clone();
+        self.is_visible
+    }
+
+    pub fn is_pressed(&self, chip8_keycode: u8) -> bool {
+        self.pressed_keycodes.contains(&chip8_keycode)
+    }
+
+    pub fn label(&self, chip8_keycode: u8) -> &str {
+        self.key_labels.get(chip8_keycode as usize).map_or("", String::as_str)
+    }
+
+    /// CHIP-8 key at the point in the window, if the overlay is visible.
+    pub fn key_at(&self, viewport: &Viewport, x: i32, y: i32) -> Option<u8> {
+        if !self.is_visible {
+            return None;
+        }
+
+        key_rects(viewport).into_iter()
+            .find(|(_, rect)| rect.contains_point((x, y)))
+            .map(|(chip8_keycode, _)| chip8_keycode)
+    }
+}
+
+/// Help drawn over the screen which tells what the keys do in the program, by the
+/// keyboard keys mapped to its CHIP-8 keys.
+pub struct ControlsHelp {
+    pub is_visible: bool,
+    lines: Vec<String>,
+}
+
+impl ControlsHelp {
+    /// Build the help from what each CHIP-8 key does, or from the description of the
+    /// controls when that is not known.
+    pub fn new(actions: &BTreeMap<String, String>, controls: Option<&str>, keymap: &Keymap) -> Result<Self, String> {
+        let mut actions = actions.iter()
+            .map(|(hex_digit, action)| {
+                u8::from_str_radix(hex_digit, 16).ok()
+                    .filter(|&value| value <= 0xF)
+                    .map(|chip8_keycode| (chip8_keycode, action.as_str()))
+                    .ok_or_else(|| format!("Program has an action for key '{}', expected a hex digit 0-F.", hex_digit))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        actions.sort();
+
+        // Keys which are not mapped to a keyboard key can still be clicked on the keypad
+        // overlay, so they are shown by their hex digit.
+        let keys: Vec<String> = actions.iter()
+            .map(|&(chip8_keycode, _)| {
+                let names: Vec<String> = keymap.keycodes(chip8_keycode).iter().map(|keycode| keycode.name()).collect();
+                if names.is_empty() { format!("({:X})", chip8_keycode) } else { names.join(", ") }
+            })
+            .collect();
+        let width = keys.iter().map(|keys| keys.chars().count()).max().unwrap_or(0);
+
+        let lines = if actions.is_empty() {
+            vec![controls.unwrap_or("No controls are known for this program.").to_owned()]
+        } else {
+            keys.iter()
+                .zip(actions.iter())
+                .map(|(keys, (_, action))| format!("{:<width$}  {}", keys, action, width = width))
+                .collect()
+        };
+
+        Ok(ControlsHelp { is_visible: false, lines })
+    }
+
+    pub fn toggle(&mut self) {
+        self.is_visible = !self.is_visible;
+
+        debug!("Switched controls help: visible={}", self.is_visible);
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+/// Areas of CHIP-8 keys laid out in a 4x4 grid, centered in the viewport and taking
+/// most of its height.
+pub fn key_rects(viewport: &Viewport) -> Vec<(u8, Rect)> {
+    let cell_size = (viewport.width.min(viewport.height) * 9 / 10 / 4) as i32;
+    let gap = (cell_size / 10).max(1);
+    let left = viewport.x + (viewport.width as i32 - 4 * cell_size) / 2;
+    let top = viewport.y + (viewport.height as i32 - 4 * cell_size) / 2;
+
+    KEYPAD_LAYOUT.iter()
+        .enumerate()
+        .map(|(index, &chip8_keycode)| {
+            let column = (index % 4) as i32;
+            let row = (index / 4) as i32;
+            let rect = Rect::new(
+                left + column * cell_size + gap / 2,
+                top + row * cell_size + gap / 2,
+                (cell_size - gap) as u32,
+                (cell_size - gap) as u32,
+            );
+
+            (chip8_keycode, rect)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[path = "./overlay_test.rs"]
+mod overlay_test;
@@ -0,0 +1,98 @@
+#![cfg(test)]
+use speculate::speculate;
+use maplit::{btreemap, btreeset};
+
+use super::*;
+
+speculate! {
+    describe "key_rects" {
+        test "lay out keys in a centered 4x4 grid as on the original keypad" {
+            let viewport = Viewport { x: 0, y: 0, width: 640, height: 320 };
+
+            let rects = key_rects(&viewport);
+
+            assert_eq!(rects.len(), 16);
+            assert_eq!(rects[0], (0x1, Rect::new(179, 19, 65, 65)));
+            assert_eq!(rects[3].0, 0xC);
+            assert_eq!(rects[15], (0xF, Rect::new(395, 235, 65, 65)));
+        }
+    }
+
+    describe "key_at" {
+        test "find the key under the point" {
+            let viewport = Viewport { x: 0, y: 0, width: 640, height: 320 };
+            let overlay = KeypadOverlay::new(Vec::new(), true);
+
+            assert_eq!(overlay.key_at(&viewport, 180, 22), Some(0x1));
+            assert_eq!(overlay.key_at(&viewport, 400, 240), Some(0xF));
+            assert_eq!(overlay.key_at(&viewport, 10, 10), None);
+        }
+
+        test "find no key when the overlay is hidden" {
+            let viewport = Viewport { x: 0, y: 0, width: 640, height: 320 };
+            let overlay = KeypadOverlay::new(Vec::new(), false);
+
+            assert_eq!(overlay.key_at(&viewport, 180, 22), None);
+        }
+    }
+
+    describe "set_pressed_keycodes" {
+        test "ask to draw again only when pressed keys change while visible" {
+            let mut overlay = KeypadOverlay::new(Vec::new(), true);
+
+            assert_eq!(overlay.set_pressed_keycodes(&btreeset!{ 0x5 }), true);
+            assert_eq!(overlay.set_pressed_keycodes(&btreeset!{ 0x5 }), false);
+
+            overlay.toggle();
+            assert_eq!(overlay.set_pressed_keycodes(&btreeset!{}), false);
+            assert_eq!(overlay.is_pressed(0x5), false);
+        }
+    }
+
+    describe "controls_help" {
+        test "list what each key does by the keyboard keys mapped to it" {
+            let keymap = Keymap::preset("qwerty").unwrap();
+            let keymap = Keymap::from_names("qwerty", Some(&keymap), &btreemap!{ "Up".to_owned() => "C".to_owned() }).unwrap();
+            let actions = btreemap!{
+                "C".to_owned() => "Right player up".to_owned(),
+                "1".to_owned() => "Left player up".to_owned(),
+                "4".to_owned() => "Left player down".to_owned(),
+            };
+
+            let help = ControlsHelp::new(&actions, Some("Left player: 1/4"), &keymap).unwrap();
+
+            assert_eq!(help.lines(), [
+                "1      Left player up",
+                "Q      Left player down",
+                "4, Up  Right player up",
+            ]);
+            assert_eq!(help.is_visible, false);
+        }
+
+        test "show hex digits of keys which are not mapped" {
+            let keymap = Keymap::from_names("custom", None, &btreemap!{}).unwrap();
+            let actions = btreemap!{ "a".to_owned() => "Start".to_owned() };
+
+            let help = ControlsHelp::new(&actions, None, &keymap).unwrap();
+
+            assert_eq!(help.lines(), ["(A)  Start"]);
+        }
+
+        test "fall back to the description of the controls" {
+            let keymap = Keymap::preset("qwerty").unwrap();
+
+            let help = ControlsHelp::new(&btreemap!{}, Some("1-9: pick a square"), &keymap).unwrap();
+            assert_eq!(help.lines(), ["1-9: pick a square"]);
+
+            let help = ControlsHelp::new(&btreemap!{}, None, &keymap).unwrap();
+            assert_eq!(help.lines(), ["No controls are known for this program."]);
+        }
+
+        test "reject actions of keys which are not hex digits" {
+            let keymap = Keymap::preset("qwerty").unwrap();
+            let actions = btreemap!{ "G".to_owned() => "Start".to_owned() };
+
+            assert!(ControlsHelp::new(&actions, None, &keymap).is_err());
+        }
+    }
+}
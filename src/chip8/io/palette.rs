@@ -0,0 +1,131 @@
+use sdl2::pixels::Color;
+
+pub const PALETTE_DEFAULT: &str = "default";
+
+/// Each palette holds 4 colours: background, foreground (first bitplane), second
+/// bitplane and the blend of both bitplanes. The last two are only used by XO-CHIP
+/// programs, monochrome programs draw with background and foreground colours.
+pub const PALETTE_SIZE: usize = 4;
+
+const BUILT_IN_PALETTES: [(&str, [u32; PALETTE_SIZE]); 5] = [
+    ("default", [0x000000, 0xFFFFFF, 0xAAAAAA, 0x555555]),
+    ("amber",   [0x1A1000, 0xFFB000, 0xB37B00, 0x664600]),
+    ("green",   [0x0A140A, 0x33FF66, 0x23B347, 0x146629]),
+    ("lcd",     [0x9BBC0F, 0x0F380F, 0x306230, 0x8BAC0F]),
+    ("octo",    [0x996600, 0xFFCC00, 0xFF6600, 0x662200]),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub name: String,
+    pub colors: [Color; PALETTE_SIZE],
+}
+
+impl Palette {
+    pub fn built_in(name: &str) -> Option<Self> {
+        BUILT_IN_PALETTES.iter()
+            .find(|(built_in_name, _)| *built_in_name == name)
+            .map(|(name, rgbs)| Palette {
+                name: (*name).to_owned(),
+                colors: [rgb(rgbs[0]), rgb(rgbs[1]), rgb(rgbs[2]), rgb(rgbs[3])],
+            })
+    }
+
+    pub fn built_in_names() -> Vec<&'static str> {
+        BUILT_IN_PALETTES.iter().map(|(name, _)| *name).collect()
+    }
+
+    /// Build a palette from a list of 2 or 4 hex colours (`#RRGGBB` or `RRGGBB`).
+    /// When only background and foreground colours are given, both bitplanes are
+    /// drawn with the foreground colour.
+    pub fn from_hex<S: AsRef<str>>(name: &str, hex_colors: &[S]) -> Result<Self, String> {
+        let colors = hex_colors.iter()
+            .map(|hex| parse_color(hex.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let colors = match colors[..] {
+            [bg, fg]            => [bg, fg, fg, fg],
+            [bg, fg, fg2, both] => [bg, fg, fg2, both],
+            _                   => return Err(format!("Palette '{}' must have 2 or 4 colours, but has {}.", name, colors.len())),
+        };
+
+        Ok(Palette { name: name.to_owned(), colors })
+    }
+
+    /// Resolve a palette argument, which is either a name of a built-in palette, a name
+    /// of a custom palette or a comma-separated list of hex colours.
+    pub fn parse(value: &str, custom_palettes: &[Palette]) -> Result<Self, String> {
+        if let Some(palette) = Self::built_in(value) {
+            return Ok(palette);
+        }
+
+        if let Some(palette) = custom_palettes.iter().find(|palette| palette.name == value) {
+            return Ok(palette.clone());
+        }
+
+        let hex_colors: Vec<&str> = value.split(',').map(str::trim).collect();
+        Self::from_hex(value, &hex_colors)
+            .map_err(|e| format!("Unknown palette '{}': {}", value, e))
+    }
+
+    pub fn background(&self) -> Color {
+        self.colors[0]
+    }
+
+    pub fn foreground(&self) -> Color {
+        self.colors[1]
+    }
+}
+
+/// Cycles through available palettes, starting with the selected one.
+pub struct Palettes {
+    palettes: Vec<Palette>,
+    index: usize,
+}
+
+impl Palettes {
+    pub fn new(selected: Palette, custom_palettes: Vec<Palette>) -> Self {
+        let others: Vec<Palette> = Palette::built_in_names().into_iter()
+            .filter_map(Palette::built_in)
+            .chain(custom_palettes)
+            .filter(|palette| palette.name != selected.name)
+            .collect();
+
+        let mut palettes = vec![selected];
+        palettes.extend(others);
+
+        Palettes { palettes, index: 0 }
+    }
+
+    pub fn current(&self) -> &Palette {
+        &self.palettes[self.index]
+    }
+
+    pub fn cycle(&mut self) -> &Palette {
+        self.index = (self.index + 1) % self.palettes.len();
+
+        debug!("Switched palette: name={}", self.current().name);
+
+        self.current()
+    }
+}
+
+pub fn parse_color(hex: &str) -> Result<Color, String> {
+    let digits = hex.trim_start_matches('#');
+
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid colour '{}', expected #RRGGBB.", hex));
+    }
+
+    let value = u32::from_str_radix(digits, 16).map_err(|e| e.to_string())?;
+
+    Ok(rgb(value))
+}
+
+fn rgb(value: u32) -> Color {
+    Color::RGB((value >> 16) as u8, (value >> 8) as u8, value as u8)
+}
+
+#[cfg(test)]
+#[path = "./palette_test.rs"]
+mod palette_test;
@@ -0,0 +1,82 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "parse_color" {
+        test "parse a hex colour with or without leading #" {
+            assert_eq!(parse_color("#FFB000").unwrap(), Color::RGB(0xFF, 0xB0, 0x00));
+            assert_eq!(parse_color("0f380f").unwrap(), Color::RGB(0x0F, 0x38, 0x0F));
+        }
+
+        test "reject malformed colours" {
+            assert!(parse_color("#FFF").is_err());
+            assert!(parse_color("#GGGGGG").is_err());
+            assert!(parse_color("+12345").is_err());
+        }
+    }
+
+    describe "from_hex" {
+        test "use foreground colour for both bitplanes when 2 colours are given" {
+            let palette = Palette::from_hex("custom", &["#000000", "#FFFFFF"]).unwrap();
+
+            assert_eq!(palette.background(), Color::BLACK);
+            assert_eq!(palette.colors[1..], [Color::WHITE; 3]);
+        }
+
+        test "use all 4 colours when they are given" {
+            let palette = Palette::from_hex("custom", &["#000000", "#FF0000", "#00FF00", "#0000FF"]).unwrap();
+
+            assert_eq!(palette.colors, [Color::BLACK, Color::RED, Color::GREEN, Color::BLUE]);
+        }
+
+        test "reject any other number of colours" {
+            assert!(Palette::from_hex("custom", &["#000000"]).is_err());
+            assert!(Palette::from_hex("custom", &["#000000", "#FFFFFF", "#FFFFFF"]).is_err());
+        }
+    }
+
+    describe "parse" {
+        test "resolve a built-in palette by name" {
+            let palette = Palette::parse("amber", &[]).unwrap();
+
+            assert_eq!(palette, Palette::built_in("amber").unwrap());
+        }
+
+        test "resolve a custom palette by name" {
+            let custom_palettes = vec![Palette::from_hex("mine", &["#101010", "#F0F0F0"]).unwrap()];
+
+            let palette = Palette::parse("mine", &custom_palettes).unwrap();
+
+            assert_eq!(palette, custom_palettes[0]);
+        }
+
+        test "resolve a comma-separated list of colours" {
+            let palette = Palette::parse("#000000, #00FF00", &[]).unwrap();
+
+            assert_eq!(palette.foreground(), Color::GREEN);
+        }
+
+        test "reject an unknown palette" {
+            assert!(Palette::parse("sepia", &[]).is_err());
+        }
+    }
+
+    describe "palettes" {
+        test "start with the selected palette" {
+            let palettes = Palettes::new(Palette::built_in("lcd").unwrap(), vec![]);
+
+            assert_eq!(palettes.current().name, "lcd");
+        }
+
+        test "cycle through built-in and custom palettes, and wrap around" {
+            let custom = Palette::from_hex("mine", &["#101010", "#F0F0F0"]).unwrap();
+            let mut palettes = Palettes::new(Palette::built_in(PALETTE_DEFAULT).unwrap(), vec![custom]);
+
+            let names: Vec<String> = (0..6).map(|_| palettes.cycle().name.clone()).collect();
+
+            assert_eq!(names, ["amber", "green", "lcd", "octo", "mine", "default"]);
+        }
+    }
+}
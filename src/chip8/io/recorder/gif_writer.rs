@@ -0,0 +1,128 @@
+use std::borrow::Cow;
+use std::io::Write;
+use gif::{Encoder, Frame, Repeat};
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+use crate::chip8::io::palette::Palette;
+use super::FrameWriter;
+
+const FRAMES_PER_SECOND: u32 = 60;
+
+// GIF frame delays are in hundredths of a second, and most viewers play frames with
+// shorter delays than this much slower. Frames which would be shown for a shorter time
+// are skipped, which also removes flicker typical for CHIP-8 programs.
+const DELAY_MIN: u32 = 2;
+
+/// Writes an animated GIF. Identical consecutive frames are merged into one frame with
+/// a longer delay, and delays are rounded so that the total duration stays exact.
+pub struct GifWriter<W: Write> {
+    encoder: Option<Encoder<W>>,
+    scale: usize,
+    pending: Option<([[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], Palette)>,
+    frames_captured: u32,
+    delay_written: u32,
+}
+
+impl<W: Write> GifWriter<W> {
+    pub fn new(out: W, scale: u32, palette: &Palette) -> Result<Self, String> {
+        let width = (CHIP8_WIDTH as u32 * scale) as u16;
+        let height = (CHIP8_HEIGHT as u32 * scale) as u16;
+
+        let mut encoder = Encoder::new(out, width, height, &color_table(palette))
+            .map_err(|e| e.to_string())?;
+        encoder.set_repeat(Repeat::Infinite)
+            .map_err(|e| e.to_string())?;
+
+        Ok(GifWriter {
+            encoder: Some(encoder),
+            scale: scale as usize,
+            pending: None,
+            frames_captured: 0,
+            delay_written: 0,
+        })
+    }
+
+    fn write_pending(&mut self, delay: u32) -> Result<(), String> {
+        let (pixels, palette) = match &self.pending {
+            Some(pending) => pending,
+            None          => return Ok(()),
+        };
+        let encoder = self.encoder.as_mut().ok_or("Recording is already finished")?;
+
+        let frame = Frame {
+            width: (CHIP8_WIDTH * self.scale) as u16,
+            height: (CHIP8_HEIGHT * self.scale) as u16,
+            delay: delay as u16,
+            palette: Some(color_table(palette)),
+            buffer: Cow::Owned(color_indices(pixels, self.scale)),
+            ..Frame::default()
+        };
+        encoder.write_frame(&frame).map_err(|e| e.to_string())?;
+
+        self.delay_written += delay;
+        Ok(())
+    }
+
+    fn delay_pending(&self) -> u32 {
+        let delay_captured = (self.frames_captured * 100 + FRAMES_PER_SECOND / 2) / FRAMES_PER_SECOND;
+
+        delay_captured - self.delay_written
+    }
+}
+
+impl<W: Write> FrameWriter for GifWriter<W> {
+    fn write_frame(&mut self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], palette: &Palette) -> Result<(), String> {
+        if let Some((pending_pixels, pending_palette)) = &self.pending {
+            if pending_pixels == pixels && pending_palette == palette {
+                self.frames_captured += 1;
+                return Ok(());
+            }
+
+            let delay = self.delay_pending();
+            if delay >= DELAY_MIN {
+                self.write_pending(delay)?;
+            }
+        }
+
+        self.pending = Some((*pixels, palette.clone()));
+        self.frames_captured += 1;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        let delay = self.delay_pending().max(DELAY_MIN);
+        self.write_pending(delay)?;
+        self.pending = None;
+
+        // Encoder writes the GIF trailer when it is dropped.
+        self.encoder = None;
+        Ok(())
+    }
+}
+
+fn color_table(palette: &Palette) -> Vec<u8> {
+    palette.colors.iter()
+        .flat_map(|color| vec![color.r, color.g, color.b])
+        .collect()
+}
+
+// Background and foreground are first two colours of the palette.
+fn color_indices(pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], scale: usize) -> Vec<u8> {
+    let mut indices = Vec::with_capacity(CHIP8_WIDTH * CHIP8_HEIGHT * scale * scale);
+
+    for row in pixels.iter() {
+        for _ in 0..scale {
+            for &pixel in row.iter() {
+                for _ in 0..scale {
+                    indices.push(pixel as u8);
+                }
+            }
+        }
+    }
+
+    indices
+}
+
+#[cfg(test)]
+#[path = "./gif_writer_test.rs"]
+mod gif_writer_test;
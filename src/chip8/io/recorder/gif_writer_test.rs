@@ -0,0 +1,68 @@
+#![cfg(test)]
+use speculate::speculate;
+use gif::DecodeOptions;
+use crate::chip8::io::palette::PALETTE_DEFAULT;
+
+use super::*;
+
+speculate! {
+    describe "gif_writer" {
+        test "merge identical frames into one frame" {
+            let frames = [(blank(), 60)];
+
+            let delays = record(&frames);
+
+            assert_eq!(delays, [100]);
+        }
+
+        test "skip frames shown for a shorter time than the minimum delay, keeping the total duration" {
+            let frames = [(blank(), 4), (with_pixel(0, 0), 1), (with_pixel(1, 1), 6)];
+
+            let delays = record(&frames);
+
+            assert_eq!(delays, [7, 11]);
+        }
+    }
+
+    describe "color_indices" {
+        test "repeat each pixel in both directions when scaled" {
+            let indices = color_indices(&with_pixel(0, 0), 2);
+
+            assert_eq!(indices.len(), CHIP8_WIDTH * CHIP8_HEIGHT * 4);
+            assert_eq!(indices[..3], [1, 1, 0]);
+            assert_eq!(indices[CHIP8_WIDTH * 2..CHIP8_WIDTH * 2 + 3], [1, 1, 0]);
+        }
+    }
+
+    fn record(frames: &[([[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], usize)]) -> Vec<u16> {
+        let palette = Palette::built_in(PALETTE_DEFAULT).unwrap();
+        let mut bytes = Vec::new();
+
+        let mut writer = GifWriter::new(&mut bytes, 1, &palette).unwrap();
+        for (pixels, count) in frames.iter() {
+            for _ in 0..*count {
+                writer.write_frame(pixels, &palette).unwrap();
+            }
+        }
+        writer.finish().unwrap();
+        drop(writer);
+
+        let mut decoder = DecodeOptions::new().read_info(&bytes[..]).unwrap();
+        let mut delays = Vec::new();
+        while let Some(frame) = decoder.read_next_frame().unwrap() {
+            delays.push(frame.delay);
+        }
+
+        delays
+    }
+
+    fn blank() -> [[bool; CHIP8_WIDTH]; CHIP8_HEIGHT] {
+        [[false; CHIP8_WIDTH]; CHIP8_HEIGHT]
+    }
+
+    fn with_pixel(x: usize, y: usize) -> [[bool; CHIP8_WIDTH]; CHIP8_HEIGHT] {
+        let mut pixels = blank();
+        pixels[y][x] = true;
+        pixels
+    }
+}
@@ -0,0 +1,156 @@
+mod gif_writer;
+mod wav_writer;
+mod y4m_writer;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+use super::palette::Palette;
+use super::tone::Tone;
+
+pub use gif_writer::GifWriter;
+pub use wav_writer::WavWriter;
+pub use y4m_writer::Y4mWriter;
+
+/// Receives CHIP-8 screen once per 60Hz frame and encodes it into a video file.
+pub trait FrameWriter {
+    fn write_frame(&mut self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], palette: &Palette) -> Result<(), String>;
+
+    fn finish(&mut self) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoFormat {
+    /// Animated GIF.
+    Gif,
+    /// Raw YUV4MPEG2 stream, which can be converted by ffmpeg.
+    Y4m,
+}
+
+struct Recording {
+    path: PathBuf,
+    writer: Box<dyn FrameWriter>,
+}
+
+struct AudioRecording {
+    path: PathBuf,
+    writer: WavWriter<BufWriter<File>>,
+}
+
+/// Records gameplay into one or more video files, and the buzzer into an audio file, at
+/// the same time.
+pub struct Recorder {
+    name: String,
+    scale: u32,
+    recordings: Vec<Recording>,
+    audio: Option<AudioRecording>,
+}
+
+impl Recorder {
+    pub fn new(name: String, scale: u32) -> Self {
+        Recorder {
+            name,
+            scale,
+            recordings: Vec::new(),
+            audio: None,
+        }
+    }
+
+    pub fn is_recording(&self) -> bool {
+        !self.recordings.is_empty() || self.audio.is_some()
+    }
+
+    pub fn start(&mut self, format: VideoFormat, path: PathBuf, palette: &Palette) -> Result<(), String> {
+        let file = File::create(&path)
+            .map_err(|e| format!("Cannot create recording file {:?}: {}", path, e))?;
+        let file = BufWriter::new(file);
+
+        let writer: Box<dyn FrameWriter> = match format {
+            VideoFormat::Gif => Box::new(GifWriter::new(file, self.scale, palette)?),
+            VideoFormat::Y4m => Box::new(Y4mWriter::new(file, self.scale)?),
+        };
+
+        info!("Started recording: {:?}", path);
+
+        self.recordings.push(Recording { path, writer });
+        Ok(())
+    }
+
+    pub fn start_audio(&mut self, path: PathBuf, tone: Tone) -> Result<(), String> {
+        let file = File::create(&path)
+            .map_err(|e| format!("Cannot create recording file {:?}: {}", path, e))?;
+        let writer = WavWriter::new(BufWriter::new(file), tone)?;
+
+        info!("Started recording audio: {:?}", path);
+
+        self.audio = Some(AudioRecording { path, writer });
+        Ok(())
+    }
+
+    /// Stop the video recordings if there are any, otherwise start recording a GIF into
+    /// the current directory, named after the program and the current time. An audio
+    /// recording keeps running either way.
+    pub fn toggle(&mut self, palette: &Palette) -> Result<(), String> {
+        if !self.recordings.is_empty() {
+            self.stop_video();
+            return Ok(());
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_millis();
+        let path = PathBuf::from(format!("{}-{}.gif", self.name, timestamp));
+
+        self.start(VideoFormat::Gif, path, palette)
+    }
+
+    /// Record a frame, given the screen at its end and whether the buzzer was on after
+    /// each of its instructions.
+    pub fn capture(&mut self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], palette: &Palette, sound_states: &[bool]) {
+        let mut failed = Vec::new();
+        for (index, recording) in self.recordings.iter_mut().enumerate() {
+            if let Err(e) = recording.writer.write_frame(pixels, palette) {
+                error!("Cannot record frame into {:?}, recording stopped: {}", recording.path, e);
+                failed.push(index);
+            }
+        }
+
+        for index in failed.into_iter().rev() {
+            self.recordings.remove(index);
+        }
+
+        if let Some(audio) = &mut self.audio {
+            if let Err(e) = audio.writer.write_frame(sound_states) {
+                error!("Cannot record audio into {:?}, recording stopped: {}", audio.path, e);
+                self.audio = None;
+            }
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.stop_video();
+
+        if let Some(mut audio) = self.audio.take() {
+            match audio.writer.finish() {
+                Ok(()) => info!("Saved recording: {:?}", audio.path),
+                Err(e) => error!("Cannot finish recording {:?}: {}", audio.path, e),
+            }
+        }
+    }
+
+    fn stop_video(&mut self) {
+        for mut recording in self.recordings.drain(..) {
+            match recording.writer.finish() {
+                Ok(()) => info!("Saved recording: {:?}", recording.path),
+                Err(e) => error!("Cannot finish recording {:?}: {}", recording.path, e),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./recorder_test.rs"]
+mod recorder_test;
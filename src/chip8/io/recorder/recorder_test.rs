@@ -0,0 +1,54 @@
+#![cfg(test)]
+use speculate::speculate;
+use std::fs;
+use tempfile::TempDir;
+
+use super::*;
+
+speculate! {
+    describe "capture" {
+        test "record the buzzer of each frame into the audio file" {
+            let dir = TempDir::new().unwrap();
+            let path = dir.path().join("pong.wav");
+            let pixels = [[false; CHIP8_WIDTH]; CHIP8_HEIGHT];
+            let palette = Palette::built_in("default").unwrap();
+
+            let mut recorder = Recorder::new("pong".to_owned(), 1);
+            recorder.start_audio(path.clone(), Tone::default()).unwrap();
+            recorder.capture(&pixels, &palette, &[false; 9]);
+            recorder.capture(&pixels, &palette, &[true; 9]);
+            recorder.stop();
+
+            // Two frames of 735 samples of 2 bytes, after a 44 byte header. The first one
+            // is silent.
+            let bytes = fs::read(&path).unwrap();
+            assert_eq!(bytes.len(), 44 + 2940);
+            assert!(bytes[44..44 + 1470].iter().all(|&byte| byte == 0));
+            assert!(bytes[44 + 1470..].iter().any(|&byte| byte != 0));
+            assert_eq!(recorder.is_recording(), false);
+        }
+    }
+
+    describe "toggle" {
+        test "start and stop a GIF recording and leave the audio recording running" {
+            let dir = TempDir::new().unwrap();
+            let name = dir.path().join("pong").to_str().unwrap().to_owned();
+            let palette = Palette::built_in("default").unwrap();
+
+            let mut recorder = Recorder::new(name, 1);
+            recorder.start_audio(dir.path().join("pong.wav"), Tone::default()).unwrap();
+
+            recorder.toggle(&palette).unwrap();
+            assert_eq!(recorder.recordings.len(), 1);
+            assert!(recorder.audio.is_some());
+
+            recorder.toggle(&palette).unwrap();
+            assert!(recorder.recordings.is_empty());
+            assert!(recorder.audio.is_some());
+            assert_eq!(recorder.is_recording(), true);
+
+            recorder.stop();
+            assert_eq!(recorder.is_recording(), false);
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::io::{Seek, SeekFrom, Write};
+use crate::chip8::io::tone::{Tone, ToneGenerator};
+
+const SAMPLE_RATE: u32 = 44_100;
+const SAMPLES_PER_FRAME: usize = (SAMPLE_RATE / 60) as usize;
+const HEADER_SIZE: u32 = 44;
+
+/// Writes the buzzer into a 16-bit mono PCM WAV file, in emulated time: every 60Hz frame
+/// lasts exactly 1/60 of a second, however long it took to run.
+pub struct WavWriter<W: Write + Seek> {
+    out: W,
+    generator: ToneGenerator,
+    sample_count: u32,
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    pub fn new(mut out: W, tone: Tone) -> Result<Self, String> {
+        // Sizes are not known yet, they are filled in when finished.
+        write_header(&mut out, 0).map_err(|e| e.to_string())?;
+
+        Ok(WavWriter {
+            out,
+            generator: ToneGenerator::new(tone, SAMPLE_RATE as i32),
+            sample_count: 0,
+        })
+    }
+
+    /// Write a frame, given whether the buzzer was on after each instruction of the
+    /// frame.
+    pub fn write_frame(&mut self, sound_states: &[bool]) -> Result<(), String> {
+        let mut samples = vec![0.0; SAMPLES_PER_FRAME];
+        self.generator.fill_timeline(sound_states, &mut samples);
+
+        let bytes: Vec<u8> = samples.iter()
+            .flat_map(|&sample| ((sample * i16::MAX as f32) as i16).to_le_bytes().to_vec())
+            .collect();
+
+        self.out.write_all(&bytes).map_err(|e| e.to_string())?;
+        self.sample_count += SAMPLES_PER_FRAME as u32;
+        Ok(())
+    }
+
+    pub fn finish(&mut self) -> Result<(), String> {
+        self.out.seek(SeekFrom::Start(0))
+            .and_then(|_| write_header(&mut self.out, self.sample_count * 2))
+            .and_then(|_| self.out.seek(SeekFrom::End(0)))
+            .and_then(|_| self.out.flush())
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn write_header<W: Write>(out: &mut W, data_size: u32) -> std::io::Result<()> {
+    let channels: u16 = 1;
+    let bits_per_sample: u16 = 16;
+    let block_align = channels * bits_per_sample / 8;
+    let byte_rate = SAMPLE_RATE * block_align as u32;
+
+    out.write_all(b"RIFF")?;
+    out.write_all(&(HEADER_SIZE - 8 + data_size).to_le_bytes())?;
+    out.write_all(b"WAVEfmt ")?;
+    out.write_all(&16u32.to_le_bytes())?;
+    out.write_all(&1u16.to_le_bytes())?;  // PCM
+    out.write_all(&channels.to_le_bytes())?;
+    out.write_all(&SAMPLE_RATE.to_le_bytes())?;
+    out.write_all(&byte_rate.to_le_bytes())?;
+    out.write_all(&block_align.to_le_bytes())?;
+    out.write_all(&bits_per_sample.to_le_bytes())?;
+    out.write_all(b"data")?;
+    out.write_all(&data_size.to_le_bytes())
+}
+
+#[cfg(test)]
+#[path = "./wav_writer_test.rs"]
+mod wav_writer_test;
@@ -0,0 +1,48 @@
+#![cfg(test)]
+use speculate::speculate;
+use std::io::Cursor;
+
+use super::*;
+
+speculate! {
+    describe "wav_writer" {
+        fn samples(bytes: &[u8]) -> Vec<i16> {
+            bytes[HEADER_SIZE as usize..].chunks(2)
+                .map(|sample| i16::from_le_bytes([sample[0], sample[1]]))
+                .collect()
+        }
+
+        test "write a header with sizes of the data" {
+            let mut out = Cursor::new(Vec::new());
+
+            let mut writer = WavWriter::new(&mut out, Tone::default()).unwrap();
+            writer.write_frame(&[false; 9]).unwrap();
+            writer.write_frame(&[false; 9]).unwrap();
+            writer.finish().unwrap();
+
+            let bytes = out.into_inner();
+            assert_eq!(bytes[..4], b"RIFF"[..]);
+            assert_eq!(bytes[4..8], (36u32 + 2940).to_le_bytes());
+            assert_eq!(bytes[24..28], 44_100u32.to_le_bytes());
+            assert_eq!(bytes[40..44], 2940u32.to_le_bytes());
+            assert_eq!(bytes.len(), 44 + 2940);
+        }
+
+        test "play the tone only while the buzzer is on" {
+            let mut out = Cursor::new(Vec::new());
+            let mut sound_states = [false; 9];
+            sound_states[3] = true;
+
+            let mut writer = WavWriter::new(&mut out, Tone::default()).unwrap();
+            writer.write_frame(&sound_states).unwrap();
+            writer.finish().unwrap();
+
+            // Instructions take 81 or 82 of the 735 samples in a frame, and the fade out
+            // takes 220 samples.
+            let samples = samples(&out.into_inner());
+            assert!(samples[..245].iter().all(|&sample| sample == 0));
+            assert_ne!(samples[245], 0);
+            assert!(samples[600..].iter().all(|&sample| sample == 0));
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use std::io::Write;
+use sdl2::pixels::Color;
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+use crate::chip8::io::palette::Palette;
+use crate::chip8::io::screenshot::rgb_image;
+use super::FrameWriter;
+
+/// Writes a raw YUV4MPEG2 stream at 60 frames per second, with full resolution colour
+/// planes (4:4:4). Convert it with e.g. `ffmpeg -i recording.y4m recording.mp4`.
+pub struct Y4mWriter<W: Write> {
+    out: W,
+    scale: u32,
+}
+
+impl<W: Write> Y4mWriter<W> {
+    pub fn new(mut out: W, scale: u32) -> Result<Self, String> {
+        let width = CHIP8_WIDTH as u32 * scale;
+        let height = CHIP8_HEIGHT as u32 * scale;
+
+        writeln!(out, "YUV4MPEG2 W{} H{} F60:1 Ip A1:1 C444", width, height)
+            .map_err(|e| e.to_string())?;
+
+        Ok(Y4mWriter { out, scale })
+    }
+}
+
+impl<W: Write> FrameWriter for Y4mWriter<W> {
+    fn write_frame(&mut self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], palette: &Palette) -> Result<(), String> {
+        let rgb = rgb_image(pixels, palette, self.scale);
+        let yuv: Vec<(u8, u8, u8)> = rgb.chunks(3)
+            .map(|rgb| yuv(Color::RGB(rgb[0], rgb[1], rgb[2])))
+            .collect();
+
+        let y_plane: Vec<u8> = yuv.iter().map(|&(y, _, _)| y).collect();
+        let u_plane: Vec<u8> = yuv.iter().map(|&(_, u, _)| u).collect();
+        let v_plane: Vec<u8> = yuv.iter().map(|&(_, _, v)| v).collect();
+
+        self.out.write_all(b"FRAME\n")
+            .and_then(|_| self.out.write_all(&y_plane))
+            .and_then(|_| self.out.write_all(&u_plane))
+            .and_then(|_| self.out.write_all(&v_plane))
+            .map_err(|e| e.to_string())
+    }
+
+    fn finish(&mut self) -> Result<(), String> {
+        self.out.flush().map_err(|e| e.to_string())
+    }
+}
+
+// Convert RGB to limited range YCbCr (BT.601), which is what ffmpeg assumes by default.
+fn yuv(color: Color) -> (u8, u8, u8) {
+    let (r, g, b) = (color.r as f32, color.g as f32, color.b as f32);
+
+    let y = 16.0 + 0.257 * r + 0.504 * g + 0.098 * b;
+    let u = 128.0 - 0.148 * r - 0.291 * g + 0.439 * b;
+    let v = 128.0 + 0.439 * r - 0.368 * g - 0.071 * b;
+
+    (y.round() as u8, u.round() as u8, v.round() as u8)
+}
+
+#[cfg(test)]
+#[path = "./y4m_writer_test.rs"]
+mod y4m_writer_test;
@@ -0,0 +1,35 @@
+#![cfg(test)]
+use speculate::speculate;
+use crate::chip8::io::palette::PALETTE_DEFAULT;
+
+use super::*;
+
+speculate! {
+    describe "y4m_writer" {
+        test "write a stream header and a frame of Y, U and V planes" {
+            let palette = Palette::built_in(PALETTE_DEFAULT).unwrap();
+            let mut pixels = [[false; CHIP8_WIDTH]; CHIP8_HEIGHT];
+            pixels[0][0] = true;
+            let mut bytes = Vec::new();
+
+            let mut writer = Y4mWriter::new(&mut bytes, 1).unwrap();
+            writer.write_frame(&pixels, &palette).unwrap();
+            writer.finish().unwrap();
+
+            let header = b"YUV4MPEG2 W64 H32 F60:1 Ip A1:1 C444\nFRAME\n";
+            let plane_size = CHIP8_WIDTH * CHIP8_HEIGHT;
+            assert_eq!(bytes[..header.len()], header[..]);
+            assert_eq!(bytes.len(), header.len() + 3 * plane_size);
+
+            let y_plane = &bytes[header.len()..header.len() + plane_size];
+            assert_eq!(y_plane[..2], [235, 16]);
+        }
+    }
+
+    describe "yuv" {
+        test "convert black and white to limited range" {
+            assert_eq!(yuv(Color::BLACK), (16, 128, 128));
+            assert_eq!(yuv(Color::WHITE), (235, 128, 128));
+        }
+    }
+}
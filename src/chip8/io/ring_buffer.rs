@@ -0,0 +1,58 @@
+/// Fixed-size queue of samples which the emulator writes and the audio callback reads.
+/// When full, the oldest samples are dropped, which keeps the delay of the sound bounded
+/// if the emulator runs ahead of the audio device.
+pub struct RingBuffer<T> {
+    items: Vec<T>,
+    start: usize,
+    len: usize,
+}
+
+impl<T: Copy + Default> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        RingBuffer {
+            items: vec![T::default(); capacity],
+            start: 0,
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, items: &[T]) {
+        let capacity = self.items.len();
+
+        for &item in items {
+            if self.len == capacity {
+                self.start = (self.start + 1) % capacity;
+                self.len -= 1;
+            }
+
+            self.items[(self.start + self.len) % capacity] = item;
+            self.len += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Move the oldest items into `out`, and fill the rest of it with the default value
+    /// if there are not enough of them. Returns how many items were moved.
+    pub fn pop(&mut self, out: &mut [T]) -> usize {
+        let count = out.len().min(self.len);
+
+        for x in out[..count].iter_mut() {
+            *x = self.items[self.start];
+            self.start = (self.start + 1) % self.items.len();
+        }
+        self.len -= count;
+
+        for x in out[count..].iter_mut() {
+            *x = T::default();
+        }
+
+        count
+    }
+}
+
+#[cfg(test)]
+#[path = "./ring_buffer_test.rs"]
+mod ring_buffer_test;
@@ -0,0 +1,52 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "ring buffer" {
+        test "pop items in the order they were pushed" {
+            let mut buffer = RingBuffer::new(4);
+            let mut out = [0; 2];
+
+            buffer.push(&[1, 2, 3]);
+
+            assert_eq!(buffer.pop(&mut out), 2);
+            assert_eq!(out, [1, 2]);
+            assert_eq!(buffer.pop(&mut out), 1);
+        }
+
+        test "wrap around the end" {
+            let mut buffer = RingBuffer::new(4);
+            let mut out = [0; 3];
+
+            buffer.push(&[1, 2, 3]);
+            buffer.pop(&mut out);
+            buffer.push(&[4, 5, 6]);
+
+            assert_eq!(buffer.pop(&mut out), 3);
+            assert_eq!(out, [4, 5, 6]);
+            assert_eq!(buffer.pop(&mut out), 0);
+        }
+
+        test "drop the oldest items when full" {
+            let mut buffer = RingBuffer::new(4);
+            let mut out = [0; 4];
+
+            buffer.push(&[1, 2, 3, 4, 5, 6]);
+
+            buffer.pop(&mut out);
+            assert_eq!(out, [3, 4, 5, 6]);
+        }
+
+        test "fill the rest with default values when running out of items" {
+            let mut buffer = RingBuffer::new(4);
+            let mut out = [9; 4];
+
+            buffer.push(&[1]);
+
+            assert_eq!(buffer.pop(&mut out), 1);
+            assert_eq!(out, [1, 0, 0, 0]);
+        }
+    }
+}
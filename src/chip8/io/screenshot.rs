@@ -0,0 +1,117 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+use super::palette::Palette;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenshotScale {
+    /// One image pixel per CHIP-8 pixel (64x32).
+    Native,
+    /// Scaled the same as CHIP-8 pixels in the window.
+    Window,
+}
+
+impl ScreenshotScale {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "native" => Ok(ScreenshotScale::Native),
+            "window" => Ok(ScreenshotScale::Window),
+            _        => Err(format!("Unknown screenshot scale: {}.", value)),
+        }
+    }
+}
+
+pub struct Screenshots {
+    name: String,
+    scale: ScreenshotScale,
+    path_on_exit: Option<PathBuf>,
+}
+
+impl Screenshots {
+    pub fn new(name: String, scale: ScreenshotScale, path_on_exit: Option<PathBuf>) -> Self {
+        Screenshots {
+            name,
+            scale,
+            path_on_exit,
+        }
+    }
+
+    /// Save a screenshot into the current directory, named after the program and the
+    /// current time.
+    pub fn take(&self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], palette: &Palette, window_scale: u32) -> Result<PathBuf, String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_millis();
+        let path = PathBuf::from(format!("{}-{}.png", self.name, timestamp));
+
+        save_png(&path, pixels, palette, self.scale_factor(window_scale))?;
+
+        Ok(path)
+    }
+
+    /// Save a screenshot to the path given for the end of the run, if there is one.
+    pub fn take_on_exit(&self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], palette: &Palette, window_scale: u32) -> Result<Option<&Path>, String> {
+        match &self.path_on_exit {
+            Some(path) => {
+                save_png(path, pixels, palette, self.scale_factor(window_scale))?;
+                Ok(Some(path))
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn scale_factor(&self, window_scale: u32) -> u32 {
+        match self.scale {
+            ScreenshotScale::Native => 1,
+            ScreenshotScale::Window => window_scale,
+        }
+    }
+}
+
+pub fn save_png(path: &Path, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], palette: &Palette, scale: u32) -> Result<(), String> {
+    let width = (CHIP8_WIDTH as u32) * scale;
+    let height = (CHIP8_HEIGHT as u32) * scale;
+
+    let file = File::create(path)
+        .map_err(|e| format!("Cannot create screenshot file {:?}: {}", path, e))?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::RGB);
+    encoder.set_depth(png::BitDepth::Eight);
+
+    encoder.write_header()
+        .and_then(|mut writer| writer.write_image_data(&rgb_image(pixels, palette, scale)))
+        .map_err(|e| format!("Cannot write screenshot file {:?}: {}", path, e))?;
+
+    debug!("Saved screenshot: path={:?}, width={}, height={}", path, width, height);
+
+    Ok(())
+}
+
+/// Convert pixels to 8b RGB triples, row by row, with each CHIP-8 pixel repeated
+/// `scale` times in both directions.
+pub fn rgb_image(pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], palette: &Palette, scale: u32) -> Vec<u8> {
+    let scale = scale as usize;
+    let mut image = Vec::with_capacity(CHIP8_WIDTH * CHIP8_HEIGHT * scale * scale * 3);
+
+    for row in pixels.iter() {
+        for _ in 0..scale {
+            for &pixel in row.iter() {
+                let color = if pixel { palette.foreground() } else { palette.background() };
+
+                for _ in 0..scale {
+                    image.extend_from_slice(&[color.r, color.g, color.b]);
+                }
+            }
+        }
+    }
+
+    image
+}
+
+#[cfg(test)]
+#[path = "./screenshot_test.rs"]
+mod screenshot_test;
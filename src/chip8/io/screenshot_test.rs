@@ -0,0 +1,60 @@
+#![cfg(test)]
+use speculate::speculate;
+use tempfile::tempdir;
+use std::fs;
+use crate::chip8::io::palette::PALETTE_DEFAULT;
+
+use super::*;
+
+speculate! {
+    describe "rgb_image" {
+        test "convert pixels to palette colours at native resolution" {
+            let palette = Palette::built_in("amber").unwrap();
+            let mut pixels = [[false; CHIP8_WIDTH]; CHIP8_HEIGHT];
+            pixels[0][1] = true;
+
+            let image = rgb_image(&pixels, &palette, 1);
+
+            let (bg, fg) = (palette.background(), palette.foreground());
+            assert_eq!(image.len(), CHIP8_WIDTH * CHIP8_HEIGHT * 3);
+            assert_eq!(image[..6], [bg.r, bg.g, bg.b, fg.r, fg.g, fg.b]);
+        }
+
+        test "repeat each pixel in both directions when scaled" {
+            let palette = Palette::built_in(PALETTE_DEFAULT).unwrap();
+            let mut pixels = [[false; CHIP8_WIDTH]; CHIP8_HEIGHT];
+            pixels[0][0] = true;
+
+            let image = rgb_image(&pixels, &palette, 2);
+
+            let row_length = CHIP8_WIDTH * 2 * 3;
+            assert_eq!(image.len(), row_length * CHIP8_HEIGHT * 2);
+            assert_eq!(image[..9], [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00]);
+            assert_eq!(image[row_length..row_length + 9], [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00]);
+        }
+    }
+
+    describe "save_png" {
+        test "write a PNG file" {
+            let dir = tempdir().unwrap();
+            let path = dir.path().join("screenshot.png");
+            let palette = Palette::built_in(PALETTE_DEFAULT).unwrap();
+
+            save_png(&path, &[[false; CHIP8_WIDTH]; CHIP8_HEIGHT], &palette, 3).unwrap();
+
+            let contents = fs::read(&path).unwrap();
+            assert_eq!(contents[..8], [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]);
+        }
+    }
+
+    describe "take_on_exit" {
+        test "do nothing when no path is given" {
+            let screenshots = Screenshots::new("pong".to_owned(), ScreenshotScale::Native, None);
+            let palette = Palette::built_in(PALETTE_DEFAULT).unwrap();
+
+            let path = screenshots.take_on_exit(&[[false; CHIP8_WIDTH]; CHIP8_HEIGHT], &palette, 10).unwrap();
+
+            assert_eq!(path, None);
+        }
+    }
+}
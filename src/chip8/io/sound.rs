@@ -1,67 +1,133 @@
 use sdl2::Sdl;
 use sdl2::audio::{AudioDevice, AudioCallback, AudioSpecDesired};
+use super::frontend::Buzzer;
+use super::ring_buffer::RingBuffer;
+use super::tone::{Tone, ToneGenerator};
 
-const PHASE_MAX: f32 = 1.0;
-const PHASE_THRESHOLD: f32 = 0.5;
-const SOUND_FREQUENCY: f32 = 440.0;
-const SOUND_VOLUME: f32 = 0.25;
 const SAMPLING_FREQUENCY: i32 = 44_100;
+const CALLBACK_SAMPLES: u16 = 512;
+const FRAMES_PER_SECOND: u64 = 60;
 
+// Samples of frames not played yet are dropped beyond this many frames, so the sound
+// does not lag behind when the emulator runs ahead of the audio device.
+const MAX_BUFFERED_FRAMES: usize = 6;
+// Playback waits until this many frames are buffered, at the start and after running out
+// of samples, so that frames arriving a little late do not cut gaps into beeps.
+const LATENCY_FRAMES: usize = 2;
+
+/// Plays the buzzer in emulated time: every frame is rendered into samples as soon as it
+/// has run, and the audio device plays them from a ring buffer. A beep lasts exactly as
+/// long as the sound timer, however the frames are spaced in wall-clock time.
 pub struct Sound {
-    device: AudioDevice<SquareWave>,
+    device: AudioDevice<SampleQueue>,
+    generator: ToneGenerator,
+    sampling_frequency: u64,
+    frame_count: u64,
+    is_muted: bool,
 }
 
 impl Sound {
-    pub fn new(sdl: &Sdl) -> Result<Self, String> {
+    pub fn new(sdl: &Sdl, tone: Tone, is_muted: bool) -> Result<Self, String> {
         let audio = sdl.audio()?;
 
         let desired_spec = AudioSpecDesired {
             freq: Some(SAMPLING_FREQUENCY),
             channels: Some(1),  // mono
-            samples: None       // default sample size
+            samples: Some(CALLBACK_SAMPLES),
         };
 
         let device = audio.open_playback(None, &desired_spec, |spec| {
-            SquareWave {
-                phase: 0.0,
-                phase_inc: SOUND_FREQUENCY / spec.freq as f32,
-                volume: SOUND_VOLUME,
-            }
+            let samples_per_frame = spec.freq as usize / FRAMES_PER_SECOND as usize + 1;
+            SampleQueue::new(
+                samples_per_frame * MAX_BUFFERED_FRAMES,
+                samples_per_frame * LATENCY_FRAMES,
+            )
         })?;
+        device.resume();
+
+        let sampling_frequency = device.spec().freq;
+
+        debug!("Initialised sound: tone={:?}, sampling_frequency={}, muted={}", tone, sampling_frequency, is_muted);
+
+        Ok(Sound {
+            device,
+            generator: ToneGenerator::new(tone, sampling_frequency),
+            sampling_frequency: sampling_frequency as u64,
+            frame_count: 0,
+            is_muted,
+        })
+    }
 
-        Ok(Sound { device })
+    // Frames do not always take a whole number of samples, so they take the samples
+    // which start within them.
+    fn frame_sample_count(&self) -> usize {
+        let start = self.frame_count * self.sampling_frequency / FRAMES_PER_SECOND;
+        let end = (self.frame_count + 1) * self.sampling_frequency / FRAMES_PER_SECOND;
+        (end - start) as usize
     }
+}
+
+impl Buzzer for Sound {
+    fn play_frame(&mut self, sound_states: &[bool]) {
+        let mut samples = vec![0.0; self.frame_sample_count()];
+        self.frame_count += 1;
 
-    pub fn set(&self, is_on: bool) {
-        if is_on {
-            self.unmute();
+        if self.is_muted {
+            self.generator.set_on(false);
+            self.generator.fill(&mut samples);
         } else {
-            self.mute();
+            self.generator.fill_timeline(sound_states, &mut samples);
         }
-    }
 
-    pub fn mute(&self) {
-        self.device.pause();
+        self.device.lock().push(&samples);
     }
 
-    pub fn unmute(&self) {
-        self.device.resume()
+    fn toggle_mute(&mut self) -> bool {
+        self.is_muted = !self.is_muted;
+        self.is_muted
     }
 }
 
-struct SquareWave {
-    phase: f32,
-    phase_inc: f32,
-    volume: f32
+/// Samples waiting for the audio device, which plays silence until enough of them are
+/// buffered.
+pub struct SampleQueue {
+    samples: RingBuffer<f32>,
+    latency: usize,
+    is_playing: bool,
 }
 
-impl AudioCallback for SquareWave {
+impl SampleQueue {
+    pub fn new(capacity: usize, latency: usize) -> Self {
+        SampleQueue {
+            samples: RingBuffer::new(capacity),
+            latency,
+            is_playing: false,
+        }
+    }
+
+    pub fn push(&mut self, samples: &[f32]) {
+        self.samples.push(samples);
+    }
+}
+
+impl AudioCallback for SampleQueue {
     type Channel = f32;
 
     fn callback(&mut self, out: &mut [f32]) {
-        for x in out.iter_mut() {
-            *x = if self.phase <= PHASE_THRESHOLD { self.volume } else { -self.volume };
-            self.phase = (self.phase + self.phase_inc) % PHASE_MAX;
+        if !self.is_playing && self.samples.len() < self.latency {
+            for sample in out.iter_mut() {
+                *sample = 0.0;
+            }
+            return;
+        }
+
+        self.is_playing = self.samples.pop(out) == out.len();
+        if !self.is_playing {
+            debug!("Sound ran out of samples, buffering again");
         }
     }
 }
+
+#[cfg(test)]
+#[path = "./sound_test.rs"]
+mod sound_test;
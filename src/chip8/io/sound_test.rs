@@ -0,0 +1,55 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "sample_queue" {
+        test "play a continuous beep without gaps once started" {
+            let samples_per_frame = 735;
+            let mut queue = SampleQueue::new(
+                samples_per_frame * MAX_BUFFERED_FRAMES,
+                samples_per_frame * LATENCY_FRAMES,
+            );
+            let mut out = vec![0.0; CALLBACK_SAMPLES as usize];
+            let mut played = Vec::new();
+
+            // Frames arrive every 1/60 s, and the device asks for samples every 512/44100 s,
+            // for 9 seconds. Times are in microseconds.
+            let (frame_interval, callback_interval) = (16_667, 11_610);
+            let (mut next_frame, mut next_callback) = (0, callback_interval);
+            while next_callback < 9_000_000 {
+                if next_frame <= next_callback {
+                    queue.push(&vec![1.0; samples_per_frame]);
+                    next_frame += frame_interval;
+                } else {
+                    queue.callback(&mut out);
+                    played.extend_from_slice(&out);
+                    next_callback += callback_interval;
+                }
+            }
+
+            let start = played.iter().position(|&sample| sample != 0.0).unwrap();
+            assert!(start <= samples_per_frame * LATENCY_FRAMES);
+            assert!(played[start..].iter().all(|&sample| sample == 1.0));
+        }
+
+        test "stay silent after running out until samples are buffered again" {
+            let mut queue = SampleQueue::new(100, 10);
+            let mut out = [9.0; 8];
+
+            queue.push(&[1.0; 10]);
+            queue.callback(&mut out);
+            queue.callback(&mut out);
+            assert_eq!(out, [1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+
+            queue.push(&[1.0; 5]);
+            queue.callback(&mut out);
+            assert_eq!(out, [0.0; 8]);
+
+            queue.push(&[1.0; 5]);
+            queue.callback(&mut out);
+            assert_eq!(out, [1.0; 8]);
+        }
+    }
+}
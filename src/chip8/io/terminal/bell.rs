@@ -0,0 +1,38 @@
+use std::io::{stdout, Write};
+use crate::chip8::io::frontend::Buzzer;
+
+/// Rings the terminal bell each time the CHIP-8 buzzer is turned on, unless muted.
+pub struct TerminalBell {
+    is_on: bool,
+    is_muted: bool,
+}
+
+impl TerminalBell {
+    pub fn new(is_muted: bool) -> Self {
+        TerminalBell { is_on: false, is_muted }
+    }
+}
+
+impl Buzzer for TerminalBell {
+    fn play_frame(&mut self, sound_states: &[bool]) {
+        let mut was_on = self.is_on;
+        let is_turned_on = sound_states.iter().any(|&is_on| {
+            let is_turned_on = is_on && !was_on;
+            was_on = is_on;
+            is_turned_on
+        });
+        self.is_on = was_on;
+
+        if is_turned_on && !self.is_muted {
+            let mut out = stdout();
+            out.write_all(b"\x07")
+                .and_then(|_| out.flush())
+                .unwrap_or_else(|e| warn!("Cannot ring terminal bell: {}", e));
+        }
+    }
+
+    fn toggle_mute(&mut self) -> bool {
+        self.is_muted = !self.is_muted;
+        self.is_muted
+    }
+}
@@ -0,0 +1,71 @@
+use std::io::{stdout, Stdout, Write};
+use crossterm::{execute, queue};
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+use crate::chip8::io::frontend::Screen;
+use crate::chip8::io::palette::Palette;
+use super::glyphs::Glyphs;
+
+/// Draws CHIP-8 screen in the terminal. While it exists, the terminal is switched to
+/// the alternate screen and raw mode (which is also needed to read keys one by one),
+/// and it is restored when display is dropped.
+pub struct TerminalDisplay {
+    out: Stdout,
+    glyphs: Glyphs,
+    palette: Palette,
+}
+
+impl TerminalDisplay {
+    pub fn new(glyphs: Glyphs, palette: Palette) -> Result<Self, String> {
+        let mut out = stdout();
+
+        terminal::enable_raw_mode().map_err(|e| e.to_string())?;
+        execute!(out, EnterAlternateScreen, Hide).map_err(|e| e.to_string())?;
+
+        let mut display = TerminalDisplay {
+            out,
+            glyphs,
+            palette,
+        };
+        display.render(&[[false; CHIP8_WIDTH]; CHIP8_HEIGHT])?;
+
+        debug!("Initialised terminal display: glyphs={:?}, palette={}", glyphs, display.palette.name);
+
+        Ok(display)
+    }
+}
+
+impl Screen for TerminalDisplay {
+    fn render(&mut self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT]) -> Result<(), String> {
+        let foreground = self.palette.foreground();
+        let background = self.palette.background();
+
+        queue!(
+            self.out,
+            SetForegroundColor(Color::Rgb { r: foreground.r, g: foreground.g, b: foreground.b }),
+            SetBackgroundColor(Color::Rgb { r: background.r, g: background.g, b: background.b })
+        ).map_err(|e| e.to_string())?;
+
+        for (row, line) in self.glyphs.render_lines(pixels).into_iter().enumerate() {
+            queue!(self.out, MoveTo(0, row as u16), Print(line)).map_err(|e| e.to_string())?;
+        }
+
+        queue!(self.out, ResetColor).map_err(|e| e.to_string())?;
+        self.out.flush().map_err(|e| e.to_string())
+    }
+
+    fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+}
+
+impl Drop for TerminalDisplay {
+    fn drop(&mut self) {
+        execute!(self.out, ResetColor, Clear(ClearType::All), Show, LeaveAlternateScreen)
+            .unwrap_or_else(|e| warn!("Cannot restore terminal screen: {}", e));
+        terminal::disable_raw_mode()
+            .unwrap_or_else(|e| warn!("Cannot disable terminal raw mode: {}", e));
+    }
+}
@@ -0,0 +1,79 @@
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+
+const BRAILLE_BLANK: u32 = 0x2800;
+
+// Bit of a braille character which raises the dot at (column, row) of its 2x4 grid.
+const BRAILLE_DOTS: [[u32; 2]; 4] = [
+    [0x01, 0x08],
+    [0x02, 0x10],
+    [0x04, 0x20],
+    [0x40, 0x80],
+];
+
+/// Characters used to draw CHIP-8 pixels in the terminal. Terminal cells are roughly
+/// twice as high as they are wide, so each character covers several pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Glyphs {
+    /// Upper and lower half blocks, each character covers 1x2 pixels (64x16 characters).
+    HalfBlock,
+    /// Braille patterns, each character covers 2x4 pixels (32x8 characters).
+    Braille,
+}
+
+impl Glyphs {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "half-block" => Ok(Glyphs::HalfBlock),
+            "braille"    => Ok(Glyphs::Braille),
+            _            => Err(format!("Unknown terminal glyphs: {}.", value)),
+        }
+    }
+
+    /// Number of pixels covered by a single character: (width, height).
+    pub fn cell_size(&self) -> (usize, usize) {
+        match self {
+            Glyphs::HalfBlock => (1, 2),
+            Glyphs::Braille   => (2, 4),
+        }
+    }
+
+    pub fn render_lines(&self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT]) -> Vec<String> {
+        let (cell_width, cell_height) = self.cell_size();
+
+        (0..CHIP8_HEIGHT).step_by(cell_height)
+            .map(|y| {
+                (0..CHIP8_WIDTH).step_by(cell_width)
+                    .map(|x| self.glyph(pixels, x, y))
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn glyph(&self, pixels: &[[bool; CHIP8_WIDTH]; CHIP8_HEIGHT], x: usize, y: usize) -> char {
+        match self {
+            Glyphs::HalfBlock => match (pixels[y][x], pixels[y + 1][x]) {
+                (true,  true ) => '█',
+                (true,  false) => '▀',
+                (false, true ) => '▄',
+                (false, false) => ' ',
+            },
+            Glyphs::Braille => {
+                let mut code = BRAILLE_BLANK;
+
+                for (row, dots) in BRAILLE_DOTS.iter().enumerate() {
+                    for (column, dot) in dots.iter().enumerate() {
+                        if pixels[y + row][x + column] {
+                            code |= dot;
+                        }
+                    }
+                }
+
+                std::char::from_u32(code).unwrap_or(' ')
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./glyphs_test.rs"]
+mod glyphs_test;
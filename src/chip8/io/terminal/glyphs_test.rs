@@ -0,0 +1,51 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "render_lines" {
+        test "draw 64x16 half blocks" {
+            let mut pixels = [[false; CHIP8_WIDTH]; CHIP8_HEIGHT];
+            pixels[0][0] = true;
+            pixels[1][0] = true;
+            pixels[0][1] = true;
+            pixels[3][2] = true;
+
+            let lines = Glyphs::HalfBlock.render_lines(&pixels);
+
+            assert_eq!(lines.len(), 16);
+            assert!(lines.iter().all(|line| line.chars().count() == 64));
+            assert!(lines[0].starts_with("█▀ "));
+            assert!(lines[1].starts_with("  ▄ "));
+        }
+
+        test "draw 32x8 braille patterns" {
+            let mut pixels = [[false; CHIP8_WIDTH]; CHIP8_HEIGHT];
+            pixels[0][0] = true;
+            pixels[3][1] = true;
+            for row in pixels[4..8].iter_mut() {
+                row[2] = true;
+                row[3] = true;
+            }
+
+            let lines = Glyphs::Braille.render_lines(&pixels);
+
+            assert_eq!(lines.len(), 8);
+            assert!(lines.iter().all(|line| line.chars().count() == 32));
+            assert!(lines[0].starts_with("⢁⠀"));
+            assert!(lines[1].starts_with("⠀⣿"));
+        }
+    }
+
+    describe "parse" {
+        test "parse known glyphs" {
+            assert_eq!(Glyphs::parse("half-block").unwrap(), Glyphs::HalfBlock);
+            assert_eq!(Glyphs::parse("braille").unwrap(), Glyphs::Braille);
+        }
+
+        test "reject unknown glyphs" {
+            assert!(Glyphs::parse("ascii").is_err());
+        }
+    }
+}
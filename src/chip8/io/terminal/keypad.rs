@@ -0,0 +1,109 @@
+use std::collections::{HashMap, VecDeque};
+use std::mem;
+use std::time::{Duration, Instant};
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use sdl2::keyboard::Keycode;
+use crate::chip8::interface::KeyEvent;
+use crate::chip8::io::frontend::Controls;
+use crate::chip8::io::keymap::{self, Keymap};
+use crate::chip8::io::keypad::{Hotkey, KeypadState};
+
+// Terminals only report key presses (repeated while the key is held down), but not key
+// releases. A key is considered to be held down for this long after its last press.
+const KEY_HOLD_DURATION: Duration = Duration::from_millis(300);
+
+pub struct TerminalKeypad {
+    keymap: Keymap,
+    last_presses: HashMap<u8, Instant>,
+    pending_states: VecDeque<KeypadState>,
+    key_events: Vec<KeyEvent>,
+}
+
+impl TerminalKeypad {
+    pub fn new(keymap: Keymap) -> Self {
+        TerminalKeypad {
+            keymap,
+            last_presses: HashMap::new(),
+            pending_states: VecDeque::new(),
+            key_events: Vec::new(),
+        }
+    }
+
+    fn read_events(&mut self) -> Result<(), String> {
+        while event::poll(Duration::from_secs(0)).map_err(|e| e.to_string())? {
+            match event::read().map_err(|e| e.to_string())? {
+                Event::Key(key) => self.handle_key(key),
+                Event::Resize(..) => self.pending_states.push_back(KeypadState::WindowChanged),
+                Event::Mouse(..) => {},
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_key(&mut self, key: event::KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.pending_states.push_back(KeypadState::PressedEscape),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.pending_states.push_back(KeypadState::PressedEscape);
+            },
+            KeyCode::F(2) => self.pending_states.push_back(KeypadState::Hotkey(Hotkey::CyclePalette)),
+            KeyCode::F(3) => self.pending_states.push_back(KeypadState::Hotkey(Hotkey::ToggleMute)),
+            KeyCode::F(5) => self.pending_states.push_back(KeypadState::Hotkey(Hotkey::TogglePause)),
+            KeyCode::F(6) => self.pending_states.push_back(KeypadState::Hotkey(Hotkey::AdvanceFrame)),
+            KeyCode::F(7) => self.pending_states.push_back(KeypadState::Hotkey(Hotkey::ToggleSlowMotion)),
+            KeyCode::F(8) => self.pending_states.push_back(KeypadState::Hotkey(Hotkey::Reset)),
+            KeyCode::F(10) => self.pending_states.push_back(KeypadState::Hotkey(Hotkey::ToggleRecording)),
+            KeyCode::F(12) => self.pending_states.push_back(KeypadState::Hotkey(Hotkey::Screenshot)),
+            code => {
+                let keycode = terminal_keycode(code)
+                    .and_then(|keycode| self.keymap.chip8_keycode(keycode));
+
+                if let Some(keycode) = keycode {
+                    if self.last_presses.insert(keycode, Instant::now()).is_none() {
+                        self.key_events.push(KeyEvent::pressed(keycode));
+                    }
+                }
+            },
+        }
+    }
+}
+
+impl Controls for TerminalKeypad {
+    fn state(&mut self) -> KeypadState {
+        if let Err(e) = self.read_events() {
+            error!("Cannot read terminal input: {}", e);
+            return KeypadState::PressedEscape;
+        }
+
+        if let Some(state) = self.pending_states.pop_front() {
+            return state;
+        }
+
+        let now = Instant::now();
+        let key_events = &mut self.key_events;
+        self.last_presses.retain(|&keycode, pressed_at| {
+            let is_held = now.duration_since(*pressed_at) < KEY_HOLD_DURATION;
+            if !is_held {
+                key_events.push(KeyEvent::released(keycode));
+            }
+            is_held
+        });
+
+        KeypadState::KeyEvents(mem::take(&mut self.key_events))
+    }
+}
+
+// Terminal reports characters, which are mapped the same as SDL keycodes (which are
+// lowercase characters for printable keys), so that keymaps work in both frontends.
+fn terminal_keycode(code: KeyCode) -> Option<Keycode> {
+    match code {
+        KeyCode::Char(c) => keymap::char_keycode(c),
+        KeyCode::Up      => Some(Keycode::Up),
+        KeyCode::Down    => Some(Keycode::Down),
+        KeyCode::Left    => Some(Keycode::Left),
+        KeyCode::Right   => Some(Keycode::Right),
+        KeyCode::Enter   => Some(Keycode::Return),
+        _                => None,
+    }
+}
@@ -0,0 +1,9 @@
+mod bell;
+mod display;
+mod glyphs;
+mod keypad;
+
+pub use bell::TerminalBell;
+pub use display::TerminalDisplay;
+pub use glyphs::Glyphs;
+pub use keypad::TerminalKeypad;
@@ -0,0 +1,153 @@
+use std::f32::consts::PI;
+
+pub const TONE_FREQUENCY_DEFAULT: f32 = 440.0;
+pub const TONE_VOLUME_DEFAULT: f32 = 0.25;
+pub const WAVEFORM_DEFAULT: &str = "square";
+
+// Volume is faded in and out over this long, which avoids clicks when the buzzer is
+// turned on and off.
+const FADE_SECONDS: f32 = 0.005;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Waveform {
+    Square,
+    Sine,
+    Triangle,
+    /// Random levels which change twice per period, so the noise still has a pitch.
+    Noise,
+}
+
+impl Waveform {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "square"   => Ok(Waveform::Square),
+            "sine"     => Ok(Waveform::Sine),
+            "triangle" => Ok(Waveform::Triangle),
+            "noise"    => Ok(Waveform::Noise),
+            _          => Err(format!("Unknown waveform: {}.", value)),
+        }
+    }
+}
+
+/// Sound of the buzzer.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    pub frequency: f32,
+    pub volume: f32,
+    pub waveform: Waveform,
+}
+
+impl Tone {
+    pub fn new(frequency: f32, volume: f32, waveform: Waveform) -> Result<Self, String> {
+        if !(frequency > 0.0 && frequency <= 20_000.0) {
+            return Err(format!("Buzzer frequency must be between 0 and 20000 Hz, but is {}.", frequency));
+        }
+        if !(0.0..=1.0).contains(&volume) {
+            return Err(format!("Buzzer volume must be between 0 and 1, but is {}.", volume));
+        }
+
+        Ok(Tone { frequency, volume, waveform })
+    }
+}
+
+impl Default for Tone {
+    fn default() -> Self {
+        Tone {
+            frequency: TONE_FREQUENCY_DEFAULT,
+            volume: TONE_VOLUME_DEFAULT,
+            waveform: Waveform::Square,
+        }
+    }
+}
+
+/// Generates samples of the tone, fading in when turned on and fading out when turned off.
+pub struct ToneGenerator {
+    tone: Tone,
+    phase: f32,
+    phase_inc: f32,
+    gain: f32,
+    gain_step: f32,
+    is_on: bool,
+    noise_level: f32,
+    noise_state: u32,
+}
+
+impl ToneGenerator {
+    pub fn new(tone: Tone, sampling_frequency: i32) -> Self {
+        let sampling_frequency = sampling_frequency as f32;
+
+        ToneGenerator {
+            tone,
+            phase: 0.0,
+            phase_inc: tone.frequency / sampling_frequency,
+            gain: 0.0,
+            gain_step: 1.0 / (FADE_SECONDS * sampling_frequency).max(1.0),
+            is_on: false,
+            noise_level: 0.0,
+            noise_state: 0x2545_F491,
+        }
+    }
+
+    pub fn set_on(&mut self, is_on: bool) {
+        self.is_on = is_on;
+    }
+
+    pub fn fill(&mut self, out: &mut [f32]) {
+        for x in out.iter_mut() {
+            self.gain = if self.is_on {
+                (self.gain + self.gain_step).min(1.0)
+            } else {
+                (self.gain - self.gain_step).max(0.0)
+            };
+
+            *x = if self.gain > 0.0 { self.level() * self.gain * self.tone.volume } else { 0.0 };
+            self.advance_phase();
+        }
+    }
+
+    /// Fill the samples with the buzzer states spread evenly over them, e.g. states after
+    /// each instruction of a frame over the samples of that frame.
+    pub fn fill_timeline(&mut self, sound_states: &[bool], out: &mut [f32]) {
+        if sound_states.is_empty() {
+            self.fill(out);
+            return;
+        }
+
+        let count = sound_states.len();
+        for (index, &is_on) in sound_states.iter().enumerate() {
+            let start = out.len() * index / count;
+            let end = out.len() * (index + 1) / count;
+
+            self.set_on(is_on);
+            self.fill(&mut out[start..end]);
+        }
+    }
+
+    // Level of the waveform at the current phase, between -1 and 1.
+    fn level(&self) -> f32 {
+        match self.tone.waveform {
+            Waveform::Square   => if self.phase < 0.5 { 1.0 } else { -1.0 },
+            Waveform::Sine     => (2.0 * PI * self.phase).sin(),
+            Waveform::Triangle => 1.0 - 4.0 * (self.phase - 0.5).abs(),
+            Waveform::Noise    => self.noise_level,
+        }
+    }
+
+    fn advance_phase(&mut self) {
+        let previous_phase = self.phase;
+        self.phase = (self.phase + self.phase_inc) % 1.0;
+
+        let has_crossed_half = (previous_phase < 0.5) != (self.phase < 0.5);
+        if self.tone.waveform == Waveform::Noise && has_crossed_half {
+            // xorshift32
+            self.noise_state ^= self.noise_state << 13;
+            self.noise_state ^= self.noise_state >> 17;
+            self.noise_state ^= self.noise_state << 5;
+            self.noise_level = self.noise_state as f32 / u32::MAX as f32 * 2.0 - 1.0;
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./tone_test.rs"]
+mod tone_test;
@@ -0,0 +1,88 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "tone" {
+        test "reject volumes outside of 0 to 1" {
+            assert!(Tone::new(440.0, 1.5, Waveform::Sine).is_err());
+            assert!(Tone::new(440.0, -0.1, Waveform::Sine).is_err());
+        }
+
+        test "reject frequencies which are not audible" {
+            assert!(Tone::new(0.0, 0.5, Waveform::Sine).is_err());
+            assert!(Tone::new(30_000.0, 0.5, Waveform::Sine).is_err());
+        }
+
+        test "parse waveforms by name" {
+            assert_eq!(Waveform::parse("triangle"), Ok(Waveform::Triangle));
+            assert!(Waveform::parse("sawtooth").is_err());
+        }
+    }
+
+    describe "tone generator" {
+        // 100 Hz at 800 samples per second is 8 samples per period, and the fade takes
+        // 4 samples.
+        fn generator(waveform: Waveform) -> ToneGenerator {
+            ToneGenerator::new(Tone::new(100.0, 0.5, waveform).unwrap(), 800)
+        }
+
+        fn samples(generator: &mut ToneGenerator, count: usize) -> Vec<f32> {
+            let mut out = vec![f32::NAN; count];
+            generator.fill(&mut out);
+            out
+        }
+
+        test "be silent until turned on" {
+            let mut generator = generator(Waveform::Square);
+
+            assert_eq!(samples(&mut generator, 8), [0.0; 8]);
+        }
+
+        test "fade in when turned on" {
+            let mut generator = generator(Waveform::Square);
+            generator.set_on(true);
+
+            assert_eq!(samples(&mut generator, 8), [0.125, 0.25, 0.375, 0.5, -0.5, -0.5, -0.5, -0.5]);
+        }
+
+        test "fade out when turned off" {
+            let mut generator = generator(Waveform::Square);
+            generator.set_on(true);
+            samples(&mut generator, 8);
+            generator.set_on(false);
+
+            assert_eq!(samples(&mut generator, 6), [0.375, 0.25, 0.125, 0.0, 0.0, 0.0]);
+        }
+
+        test "generate triangle waves" {
+            let mut generator = generator(Waveform::Triangle);
+            generator.set_on(true);
+            samples(&mut generator, 8);
+
+            assert_eq!(samples(&mut generator, 8), [-0.5, -0.25, 0.0, 0.25, 0.5, 0.25, 0.0, -0.25]);
+        }
+
+        test "keep noise levels for half of a period" {
+            let mut generator = generator(Waveform::Noise);
+            generator.set_on(true);
+            samples(&mut generator, 8);
+
+            let out = samples(&mut generator, 8);
+
+            assert!(out[..4].iter().all(|&x| x == out[0]));
+            assert!(out[4..].iter().all(|&x| x == out[4]));
+            assert_ne!(out[0], out[4]);
+        }
+
+        test "spread buzzer states evenly over the samples" {
+            let mut generator = ToneGenerator::new(Tone::new(10.0, 1.0, Waveform::Square).unwrap(), 80);
+            let mut out = [f32::NAN; 6];
+
+            generator.fill_timeline(&[false, true, false], &mut out);
+
+            assert_eq!(out, [0.0, 0.0, 1.0, 1.0, 0.0, 0.0]);
+        }
+    }
+}
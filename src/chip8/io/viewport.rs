@@ -0,0 +1,81 @@
+use sdl2::rect::Rect;
+use crate::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scaling {
+    /// Scale CHIP-8 pixels by the largest whole number that fits the window.
+    Integer,
+    /// Scale CHIP-8 pixels to fill the window, keeping the aspect ratio.
+    Fit,
+}
+
+impl Scaling {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "integer" => Ok(Scaling::Integer),
+            "fit"     => Ok(Scaling::Fit),
+            _         => Err(format!("Unknown scaling: {}.", value)),
+        }
+    }
+}
+
+/// Area of the window in which CHIP-8 screen is drawn. It keeps the original aspect
+/// ratio and is centered in the window, leaving letterbox bars around it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Viewport {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Viewport {
+    pub fn new(window_width: u32, window_height: u32, scaling: Scaling) -> Self {
+        let chip8_width = CHIP8_WIDTH as u32;
+        let chip8_height = CHIP8_HEIGHT as u32;
+
+        let (width, height) = match scaling {
+            Scaling::Integer => {
+                let scale = (window_width / chip8_width)
+                    .min(window_height / chip8_height)
+                    .max(1);
+
+                (chip8_width * scale, chip8_height * scale)
+            },
+            Scaling::Fit => {
+                if window_width * chip8_height <= window_height * chip8_width {
+                    (window_width, window_width * chip8_height / chip8_width)
+                } else {
+                    (window_height * chip8_width / chip8_height, window_height)
+                }
+            },
+        };
+
+        Viewport {
+            x: (window_width as i32 - width as i32) / 2,
+            y: (window_height as i32 - height as i32) / 2,
+            width,
+            height,
+        }
+    }
+
+    /// Area of the window covered by CHIP-8 pixel at (x, y). Edges are rounded so
+    /// that neighbouring pixels never overlap or leave gaps between them.
+    pub fn pixel_rect(&self, x: usize, y: usize) -> Rect {
+        let left = self.width as usize * x / CHIP8_WIDTH;
+        let right = self.width as usize * (x + 1) / CHIP8_WIDTH;
+        let top = self.height as usize * y / CHIP8_HEIGHT;
+        let bottom = self.height as usize * (y + 1) / CHIP8_HEIGHT;
+
+        Rect::new(
+            self.x + left as i32,
+            self.y + top as i32,
+            (right - left) as u32,
+            (bottom - top) as u32,
+        )
+    }
+}
+
+#[cfg(test)]
+#[path = "./viewport_test.rs"]
+mod viewport_test;
@@ -0,0 +1,69 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "new" {
+        test "fill the window exactly if it has the original aspect ratio" {
+            let viewport = Viewport::new(640, 320, Scaling::Integer);
+
+            assert_eq!(viewport, Viewport { x: 0, y: 0, width: 640, height: 320 });
+        }
+
+        test "scale by a whole number and center the screen when scaling is integer" {
+            let viewport = Viewport::new(700, 400, Scaling::Integer);
+
+            assert_eq!(viewport, Viewport { x: 30, y: 40, width: 640, height: 320 });
+        }
+
+        test "scale by at least 1 when window is smaller than the original resolution" {
+            let viewport = Viewport::new(50, 20, Scaling::Integer);
+
+            assert_eq!(viewport.width, 64);
+            assert_eq!(viewport.height, 32);
+        }
+
+        test "letterbox top and bottom when window is taller than the aspect ratio" {
+            let viewport = Viewport::new(700, 400, Scaling::Fit);
+
+            assert_eq!(viewport, Viewport { x: 0, y: 25, width: 700, height: 350 });
+        }
+
+        test "letterbox left and right when window is wider than the aspect ratio" {
+            let viewport = Viewport::new(1000, 400, Scaling::Fit);
+
+            assert_eq!(viewport, Viewport { x: 100, y: 0, width: 800, height: 400 });
+        }
+    }
+
+    describe "pixel_rect" {
+        test "return a square for each pixel when scale is a whole number" {
+            let viewport = Viewport::new(700, 400, Scaling::Integer);
+
+            assert_eq!(viewport.pixel_rect(0, 0), Rect::new(30, 40, 10, 10));
+            assert_eq!(viewport.pixel_rect(63, 31), Rect::new(660, 350, 10, 10));
+        }
+
+        test "cover the whole viewport without gaps when scale is fractional" {
+            let viewport = Viewport::new(100, 50, Scaling::Fit);
+
+            let covered_width: u32 = (0..CHIP8_WIDTH).map(|x| viewport.pixel_rect(x, 0).width()).sum();
+            let covered_height: u32 = (0..CHIP8_HEIGHT).map(|y| viewport.pixel_rect(0, y).height()).sum();
+
+            assert_eq!(covered_width, 100);
+            assert_eq!(covered_height, 50);
+        }
+    }
+
+    describe "Scaling::parse" {
+        test "parse known scaling modes" {
+            assert_eq!(Scaling::parse("integer").unwrap(), Scaling::Integer);
+            assert_eq!(Scaling::parse("fit").unwrap(), Scaling::Fit);
+        }
+
+        test "reject unknown scaling modes" {
+            assert!(Scaling::parse("stretch").is_err());
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::time::{Duration, Instant, SystemTime};
+use super::filesystem::{Filesystem, Program};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Notices when a program file changes on disk, e.g. when it is built again, by
+/// checking its modification time and size every so often.
+pub struct ProgramWatcher {
+    filesystem: Filesystem,
+    path: String,
+    last_version: Option<(SystemTime, u64)>,
+    last_poll: Instant,
+}
+
+impl ProgramWatcher {
+    pub fn new(filesystem: Filesystem, path: String) -> Self {
+        let last_version = filesystem.file_version(&path);
+
+        ProgramWatcher {
+            filesystem,
+            path,
+            last_version,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Load the program again if the file changed since it was last loaded.
+    pub fn poll(&mut self) -> Option<Result<Program, String>> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+
+        let version = self.filesystem.file_version(&self.path);
+        if version.is_none() || version == self.last_version {
+            return None;
+        }
+        self.last_version = version;
+
+        Some(self.filesystem.load_program_file(&self.path))
+    }
+}
+
+#[cfg(test)]
+#[path = "./watcher_test.rs"]
+mod watcher_test;
@@ -0,0 +1,51 @@
+#![cfg(test)]
+use speculate::speculate;
+use tempfile::NamedTempFile;
+use std::env::current_dir;
+use std::io::Write;
+
+use super::*;
+
+speculate! {
+    describe "poll" {
+        fn watch(file: &NamedTempFile) -> ProgramWatcher {
+            let filesystem = Filesystem::new(current_dir().unwrap());
+            let mut watcher = ProgramWatcher::new(filesystem, file.path().to_str().unwrap().to_owned());
+            watcher.last_poll -= POLL_INTERVAL;
+            watcher
+        }
+
+        test "not load the program while the file is unchanged" {
+            let mut file = NamedTempFile::new().unwrap();
+            file.write_all(&[0x00, 0xE0]).unwrap();
+
+            let mut watcher = watch(&file);
+
+            assert!(watcher.poll().is_none());
+        }
+
+        test "load the program again once the file changes" {
+            let mut file = NamedTempFile::new().unwrap();
+            file.write_all(&[0x00, 0xE0]).unwrap();
+            let mut watcher = watch(&file);
+
+            file.write_all(&[0x12, 0x00]).unwrap();
+            file.flush().unwrap();
+
+            let program = watcher.poll().unwrap().unwrap();
+            assert_eq!(program.rom, [0x00, 0xE0, 0x12, 0x00]);
+        }
+
+        test "wait between checks of the file" {
+            let mut file = NamedTempFile::new().unwrap();
+            file.write_all(&[0x00, 0xE0]).unwrap();
+            let mut watcher = watch(&file);
+            watcher.poll();
+
+            file.write_all(&[0x12, 0x00]).unwrap();
+            file.flush().unwrap();
+
+            assert!(watcher.poll().is_none());
+        }
+    }
+}
@@ -1,5 +1,10 @@
+pub mod assembler;
 pub mod constants;
 pub mod cpu;
+pub mod disassembler;
 pub mod io;
 pub mod interface;
+pub mod octo;
+pub mod platform;
+pub mod speed;
 pub mod system;
@@ -0,0 +1,543 @@
+use std::collections::HashMap;
+use super::constants::PROGRAM_ADDRESS_START;
+
+// Compiles programs written in Octo, the structured assembly language of the Octo IDE,
+// which is what Octo cartridges hold. The core language is supported: labels (`: main`),
+// `:const`, `:alias`, `:org`, `:next`, `:unpack`, `:byte`, `:call`, conditionals
+// (`if ... then` and `if ... begin ... else ... end`), loops (`loop ... while ... again`)
+// and the statements of CHIP-8, SUPER-CHIP and XO-CHIP instructions. Compile time
+// features such as `:macro` and `:calc` are not.
+
+/// Compile the source of an Octo program, which starts at the `main` label.
+pub fn compile(source: &str) -> Result<Vec<u8>, String> {
+    let tokens = source.lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            let code = line.split('#').next().unwrap_or_default();
+            code.split_whitespace().map(move |text| Token { line: index + 1, text })
+        })
+        .collect();
+
+    let mut compiler = Compiler::new(tokens);
+    compiler.compile()?;
+    compiler.finish()
+}
+
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    line: usize,
+    text: &'a str,
+}
+
+#[derive(Clone, Copy)]
+enum Operand {
+    Register(u16),
+    Value(u16),
+}
+
+// Address which is not known yet, to be written into the instruction at the address.
+enum Fixup {
+    Address { at: usize, name: String, line: usize },
+    Long { at: usize, name: String, line: usize },
+    Unpack { at: usize, nibble: u16, name: String, line: usize },
+}
+
+struct Loop {
+    start: usize,
+    breaks: Vec<usize>,
+}
+
+struct Compiler<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    line: usize,
+    rom: Vec<u8>,
+    here: usize,
+    labels: HashMap<&'a str, u16>,
+    constants: HashMap<&'a str, u16>,
+    aliases: HashMap<&'a str, u16>,
+    fixups: Vec<Fixup>,
+    next_label: Option<&'a str>,
+    branches: Vec<usize>,
+    loops: Vec<Loop>,
+}
+
+impl<'a> Compiler<'a> {
+    fn new(tokens: Vec<Token<'a>>) -> Self {
+        Compiler {
+            tokens,
+            position: 0,
+            line: 1,
+            rom: Vec::new(),
+            here: PROGRAM_ADDRESS_START,
+            labels: HashMap::new(),
+            constants: HashMap::new(),
+            aliases: HashMap::new(),
+            fixups: Vec::new(),
+            next_label: None,
+            branches: Vec::new(),
+            loops: Vec::new(),
+        }
+    }
+
+    fn compile(&mut self) -> Result<(), String> {
+        self.jump(0x1000, "main")?;
+
+        while self.position < self.tokens.len() {
+            self.statement()
+                .map_err(|e| format!("Line {}: {}", self.line, e))?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<Vec<u8>, String> {
+        if !self.branches.is_empty() {
+            return Err("'begin' without 'end'.".to_owned());
+        }
+        if !self.loops.is_empty() {
+            return Err("'loop' without 'again'.".to_owned());
+        }
+
+        for fixup in self.fixups.iter() {
+            let (name, line) = match fixup {
+                Fixup::Address { name, line, .. } | Fixup::Long { name, line, .. } | Fixup::Unpack { name, line, .. } => (name, line),
+            };
+            let address = *self.labels.get(name.as_str())
+                .ok_or_else(|| format!("Line {}: undefined label '{}'.", line, name))?;
+
+            match *fixup {
+                Fixup::Address { at, .. } => {
+                    if address > 0xFFF {
+                        return Err(format!("Line {}: label '{}' at #{:X} is out of reach.", line, name, address));
+                    }
+                    let offset = at - PROGRAM_ADDRESS_START;
+                    self.rom[offset] |= (address >> 8) as u8;
+                    self.rom[offset + 1] = address as u8;
+                },
+                Fixup::Long { at, .. } => {
+                    let offset = at - PROGRAM_ADDRESS_START;
+                    self.rom[offset..offset + 2].copy_from_slice(&address.to_be_bytes());
+                },
+                Fixup::Unpack { at, nibble, .. } => {
+                    let offset = at - PROGRAM_ADDRESS_START;
+                    self.rom[offset + 1] = (nibble << 4 | address >> 8) as u8;
+                    self.rom[offset + 3] = address as u8;
+                },
+            }
+        }
+
+        Ok(self.rom)
+    }
+
+    fn statement(&mut self) -> Result<(), String> {
+        let token = self.next()?;
+
+        match token {
+            ":"           => self.define_label()?,
+            ":const"      => {
+                let name = self.name()?;
+                let value = self.value()?;
+                self.constants.insert(name, value);
+            },
+            ":alias"      => {
+                let name = self.name()?;
+                let register = self.register()?;
+                self.aliases.insert(name, register);
+            },
+            ":org"        => self.here = self.value()? as usize,
+            ":next"       => self.next_label = Some(self.name()?),
+            ":unpack"     => {
+                let nibble = self.value()? & 0xF;
+                let name = self.next()?;
+                let at = self.here;
+                self.instruction(0x6000)?;
+                self.instruction(0x6100)?;
+                self.fixups.push(Fixup::Unpack { at, nibble, name: name.to_owned(), line: self.line });
+            },
+            ":byte"       => {
+                let value = self.byte()?;
+                self.emit(value)?;
+            },
+            ":call"       => {
+                let name = self.next()?;
+                self.jump(0x2000, name)?;
+            },
+            ":breakpoint" => { self.next()?; },
+            ":monitor"    => { self.next()?; self.next()?; },
+            "return" | ";" => self.instruction(0x00EE)?,
+            "clear"       => self.instruction(0x00E0)?,
+            "hires"       => self.instruction(0x00FF)?,
+            "lores"       => self.instruction(0x00FE)?,
+            "scroll-down" => { let n = self.nibble()?; self.instruction(0x00C0 | n)?; },
+            "scroll-up"   => { let n = self.nibble()?; self.instruction(0x00D0 | n)?; },
+            "scroll-right" => self.instruction(0x00FB)?,
+            "scroll-left" => self.instruction(0x00FC)?,
+            "exit"        => self.instruction(0x00FD)?,
+            "audio"       => self.instruction(0xF002)?,
+            "plane"       => { let n = self.nibble()?; self.instruction(0xF001 | n << 8)?; },
+            "bcd"         => { let x = self.register()?; self.instruction(0xF033 | x << 8)?; },
+            "saveflags"   => { let x = self.register()?; self.instruction(0xF075 | x << 8)?; },
+            "loadflags"   => { let x = self.register()?; self.instruction(0xF085 | x << 8)?; },
+            "save"        => self.save_or_load(0xF055, 0x5002)?,
+            "load"        => self.save_or_load(0xF065, 0x5003)?,
+            "sprite"      => {
+                let x = self.register()?;
+                let y = self.register()?;
+                let n = self.nibble()?;
+                self.instruction(0xD000 | x << 8 | y << 4 | n)?;
+            },
+            "jump"        => { let name = self.next()?; self.jump(0x1000, name)?; },
+            "jump0"       => { let name = self.next()?; self.jump(0xB000, name)?; },
+            "native"      => { let name = self.next()?; self.jump(0x0000, name)?; },
+            "delay"       => { self.expect(":=")?; let x = self.register()?; self.instruction(0xF015 | x << 8)?; },
+            "buzzer"      => { self.expect(":=")?; let x = self.register()?; self.instruction(0xF018 | x << 8)?; },
+            "pitch"       => { self.expect(":=")?; let x = self.register()?; self.instruction(0xF03A | x << 8)?; },
+            "i"           => self.assign_i()?,
+            "if"          => self.conditional()?,
+            "else"        => {
+                let jump_at = self.branches.pop().ok_or("'else' without 'begin'.")?;
+                let at = self.here;
+                self.instruction(0x1000)?;
+                self.patch_jump(jump_at, self.here);
+                self.branches.push(at);
+            },
+            "end"         => {
+                let jump_at = self.branches.pop().ok_or("'end' without 'begin'.")?;
+                self.patch_jump(jump_at, self.here);
+            },
+            "loop"        => self.loops.push(Loop { start: self.here, breaks: Vec::new() }),
+            "while"       => {
+                let (setup, skip) = self.condition()?;
+                for opcode in setup {
+                    self.instruction(opcode)?;
+                }
+                self.instruction(invert(skip))?;
+                let at = self.here;
+                self.instruction(0x1000)?;
+                self.loops.last_mut().ok_or("'while' without 'loop'.")?.breaks.push(at);
+            },
+            "again"       => {
+                let Loop { start, breaks } = self.loops.pop().ok_or("'again' without 'loop'.")?;
+                self.instruction(0x1000 | start as u16)?;
+                for at in breaks {
+                    self.patch_jump(at, self.here);
+                }
+            },
+            _ if token.starts_with(':') => return Err(format!("'{}' is not supported, compile the program with Octo instead.", token)),
+            _ if self.is_register(token) => self.assign_register(self.register_of(token)?)?,
+            _ => match self.known_value(token)? {
+                Some(value) if !self.labels.contains_key(token) => self.emit(value as u8)?,
+                _                                              => self.jump(0x2000, token)?,
+            },
+        }
+
+        Ok(())
+    }
+
+    fn define_label(&mut self) -> Result<(), String> {
+        let name = self.name()?;
+        if self.labels.insert(name, self.here as u16).is_some() {
+            return Err(format!("label '{}' is already defined.", name));
+        }
+        Ok(())
+    }
+
+    fn save_or_load(&mut self, opcode: u16, range_opcode: u16) -> Result<(), String> {
+        let x = self.register()?;
+
+        if self.peek() == Some("-") {
+            self.next()?;
+            let y = self.register()?;
+            self.instruction(range_opcode | x << 8 | y << 4)
+        } else {
+            self.instruction(opcode | x << 8)
+        }
+    }
+
+    fn assign_i(&mut self) -> Result<(), String> {
+        match self.next()? {
+            ":=" => match self.next()? {
+                "hex"    => { let x = self.register()?; self.instruction(0xF029 | x << 8) },
+                "bighex" => { let x = self.register()?; self.instruction(0xF030 | x << 8) },
+                "long"   => {
+                    let name = self.next()?;
+                    self.instruction(0xF000)?;
+                    let at = self.here;
+                    match self.known_value(name)? {
+                        Some(value) => self.instruction(value),
+                        None        => {
+                            self.instruction(0x0000)?;
+                            self.fixups.push(Fixup::Long { at, name: name.to_owned(), line: self.line });
+                            Ok(())
+                        },
+                    }
+                },
+                name     => self.jump(0xA000, name),
+            },
+            "+=" => { let x = self.register()?; self.instruction(0xF01E | x << 8) },
+            token => Err(format!("unknown operator '{}' for i.", token)),
+        }
+    }
+
+    fn assign_register(&mut self, x: u16) -> Result<(), String> {
+        let operator = self.next()?;
+
+        if operator == ":=" {
+            match self.peek() {
+                Some("delay")  => { self.next()?; return self.instruction(0xF007 | x << 8); },
+                Some("key")    => { self.next()?; return self.instruction(0xF00A | x << 8); },
+                Some("random") => { self.next()?; let kk = self.byte()? as u16; return self.instruction(0xC000 | x << 8 | kk); },
+                _              => {},
+            }
+        }
+
+        let opcode = match (operator, self.operand()?) {
+            (":=",  Operand::Register(y)) => 0x8000 | x << 8 | y << 4,
+            (":=",  Operand::Value(kk))   => 0x6000 | x << 8 | kk,
+            ("+=",  Operand::Register(y)) => 0x8004 | x << 8 | y << 4,
+            ("+=",  Operand::Value(kk))   => 0x7000 | x << 8 | kk,
+            ("-=",  Operand::Register(y)) => 0x8005 | x << 8 | y << 4,
+            ("-=",  Operand::Value(kk))   => 0x7000 | x << 8 | (kk as u8).wrapping_neg() as u16,
+            ("=-",  Operand::Register(y)) => 0x8007 | x << 8 | y << 4,
+            ("|=",  Operand::Register(y)) => 0x8001 | x << 8 | y << 4,
+            ("&=",  Operand::Register(y)) => 0x8002 | x << 8 | y << 4,
+            ("^=",  Operand::Register(y)) => 0x8003 | x << 8 | y << 4,
+            (">>=", Operand::Register(y)) => 0x8006 | x << 8 | y << 4,
+            ("<<=", Operand::Register(y)) => 0x800E | x << 8 | y << 4,
+            _ => return Err(format!("unknown operator '{}' for a register and this operand.", operator)),
+        };
+
+        self.instruction(opcode)
+    }
+
+    fn conditional(&mut self) -> Result<(), String> {
+        let (setup, skip) = self.condition()?;
+        for opcode in setup {
+            self.instruction(opcode)?;
+        }
+
+        match self.next()? {
+            "then"  => self.instruction(skip),
+            "begin" => {
+                self.instruction(invert(skip))?;
+                self.branches.push(self.here);
+                self.instruction(0x1000)
+            },
+            token   => Err(format!("expected 'then' or 'begin', but got '{}'.", token)),
+        }
+    }
+
+    // Instructions to run before the skip, and a skip instruction which skips the next
+    // instruction when the condition is false. Comparisons other than equality use vf.
+    fn condition(&mut self) -> Result<(Vec<u16>, u16), String> {
+        let x = self.register()?;
+
+        let operator = self.next()?;
+        match operator {
+            "key"  => return Ok((Vec::new(), 0xE0A1 | x << 8)),
+            "-key" => return Ok((Vec::new(), 0xE09E | x << 8)),
+            _      => {},
+        }
+
+        let operand = self.operand()?;
+        let load_vf = match operand {
+            Operand::Register(y) => 0x8F00 | y << 4,
+            Operand::Value(kk)   => 0x6F00 | kk,
+        };
+
+        let condition = match (operator, operand) {
+            ("==", Operand::Register(y)) => (Vec::new(), 0x9000 | x << 8 | y << 4),
+            ("==", Operand::Value(kk))   => (Vec::new(), 0x4000 | x << 8 | kk),
+            ("!=", Operand::Register(y)) => (Vec::new(), 0x5000 | x << 8 | y << 4),
+            ("!=", Operand::Value(kk))   => (Vec::new(), 0x3000 | x << 8 | kk),
+            (">", _)                     => (vec![load_vf, 0x8F05 | x << 4], 0x3F01),
+            ("<=", _)                    => (vec![load_vf, 0x8F05 | x << 4], 0x3F00),
+            ("<", _)                     => (vec![load_vf, 0x8F07 | x << 4], 0x3F01),
+            (">=", _)                    => (vec![load_vf, 0x8F07 | x << 4], 0x3F00),
+            _ => return Err(format!("unknown comparison '{}'.", operator)),
+        };
+
+        if x == 0xF && !condition.0.is_empty() {
+            return Err(format!("cannot compare vf with '{}', since it is used for the comparison.", operator));
+        }
+
+        Ok(condition)
+    }
+
+    // Instruction with an address, which may be a label defined later.
+    fn jump(&mut self, opcode: u16, name: &'a str) -> Result<(), String> {
+        let at = self.here;
+
+        match self.known_value(name)? {
+            Some(address) if address <= 0xFFF => self.instruction(opcode | address),
+            Some(address)                     => Err(format!("address #{:X} is out of reach.", address)),
+            None                              => {
+                self.instruction(opcode)?;
+                self.fixups.push(Fixup::Address { at, name: name.to_owned(), line: self.line });
+                Ok(())
+            },
+        }
+    }
+
+    fn patch_jump(&mut self, at: usize, target: usize) {
+        let offset = at - PROGRAM_ADDRESS_START;
+        self.rom[offset] = 0x10 | (target >> 8) as u8;
+        self.rom[offset + 1] = target as u8;
+    }
+
+    fn instruction(&mut self, opcode: u16) -> Result<(), String> {
+        if let Some(name) = self.next_label.take() {
+            self.labels.insert(name, self.here as u16 + 1);
+        }
+
+        let [high, low] = opcode.to_be_bytes();
+        self.emit(high)?;
+        self.emit(low)
+    }
+
+    fn emit(&mut self, byte: u8) -> Result<(), String> {
+        let offset = self.here.checked_sub(PROGRAM_ADDRESS_START)
+            .ok_or_else(|| format!("address #{:X} is below the program space.", self.here))?;
+
+        if offset >= self.rom.len() {
+            self.rom.resize(offset + 1, 0);
+        }
+        self.rom[offset] = byte;
+        self.here += 1;
+
+        Ok(())
+    }
+
+    fn operand(&mut self) -> Result<Operand, String> {
+        let token = self.peek().ok_or("unexpected end of program.")?;
+
+        if self.is_register(token) {
+            Ok(Operand::Register(self.register()?))
+        } else {
+            Ok(Operand::Value(self.byte()? as u16))
+        }
+    }
+
+    fn register(&mut self) -> Result<u16, String> {
+        let token = self.next()?;
+        self.register_of(token)
+    }
+
+    fn register_of(&self, token: &str) -> Result<u16, String> {
+        if let Some(&register) = self.aliases.get(token) {
+            return Ok(register);
+        }
+
+        let lowercase = token.to_lowercase();
+        match lowercase.strip_prefix('v') {
+            Some(digit) if digit.len() == 1 => u16::from_str_radix(digit, 16)
+                .map_err(|_| format!("expected a register, but got '{}'.", token)),
+            _ => Err(format!("expected a register, but got '{}'.", token)),
+        }
+    }
+
+    fn is_register(&self, token: &str) -> bool {
+        self.register_of(token).is_ok()
+    }
+
+    fn nibble(&mut self) -> Result<u16, String> {
+        let value = self.value()?;
+        if value > 0xF {
+            return Err(format!("value {} does not fit into 4 bits.", value));
+        }
+        Ok(value)
+    }
+
+    // Byte values can be negative, e.g. `-1` for 255.
+    fn byte(&mut self) -> Result<u8, String> {
+        let token = self.next()?;
+        let value = self.known_value(token)?
+            .ok_or_else(|| format!("unknown constant '{}'.", token))?;
+
+        if value > 0xFF && value < 0xFF80 {
+            return Err(format!("value {} does not fit into a byte.", token));
+        }
+        Ok(value as u8)
+    }
+
+    fn value(&mut self) -> Result<u16, String> {
+        let token = self.next()?;
+        self.known_value(token)?
+            .ok_or_else(|| format!("unknown constant '{}'.", token))
+    }
+
+    // Value of a number, constant or label which is already defined.
+    fn known_value(&self, token: &str) -> Result<Option<u16>, String> {
+        if let Some(&value) = self.constants.get(token).or_else(|| self.labels.get(token)) {
+            return Ok(Some(value));
+        }
+
+        let (is_negative, digits) = match token.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None         => (false, token),
+        };
+        let number = if let Some(hex) = digits.strip_prefix("0x") {
+            i32::from_str_radix(hex, 16).ok()
+        } else if let Some(binary) = digits.strip_prefix("0b") {
+            i32::from_str_radix(binary, 2).ok()
+        } else if digits.starts_with(|c: char| c.is_ascii_digit()) {
+            Some(digits.parse::<i32>().map_err(|_| format!("invalid number '{}'.", token))?)
+        } else {
+            None
+        };
+
+        match number {
+            Some(number) if number > 0xFFFF => Err(format!("value {} does not fit into 16 bits.", token)),
+            Some(number) if is_negative     => Ok(Some((-number) as u16)),
+            Some(number)                    => Ok(Some(number as u16)),
+            None                            => Ok(None),
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, String> {
+        let token = self.next()?;
+
+        if token.starts_with(|c: char| c.is_ascii_digit() || c == '-') || self.is_register(token) {
+            return Err(format!("'{}' cannot be used as a name.", token));
+        }
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), String> {
+        match self.next()? {
+            token if token == expected => Ok(()),
+            token                      => Err(format!("expected '{}', but got '{}'.", expected, token)),
+        }
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).map(|token| token.text)
+    }
+
+    fn next(&mut self) -> Result<&'a str, String> {
+        let token = self.tokens.get(self.position)
+            .copied()
+            .ok_or("unexpected end of program.")?;
+
+        self.position += 1;
+        self.line = token.line;
+
+        Ok(token.text)
+    }
+}
+
+// Skip instruction which skips when the condition is true instead of false.
+fn invert(skip: u16) -> u16 {
+    match skip & 0xF000 {
+        0x3000 => skip & 0x0FFF | 0x4000,
+        0x4000 => skip & 0x0FFF | 0x3000,
+        0x5000 => skip & 0x0FFF | 0x9000,
+        0x9000 => skip & 0x0FFF | 0x5000,
+        _      => skip ^ (0x009E ^ 0x00A1),
+    }
+}
+
+#[cfg(test)]
+#[path = "./octo_test.rs"]
+mod octo_test;
@@ -0,0 +1,115 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "compile" {
+        test "jump to main and compile statements, labels and data" {
+            let source = "
+                : sprite 0xF0 0x90  # data
+                : main
+                    i := sprite
+                    v0 := 5
+                    v1 += -1
+                    sprite v0 v1 2
+                    draw
+                    jump main
+                : draw
+                    clear
+                ;
+            ";
+
+            let program = compile(source).unwrap();
+
+            assert_eq!(program, [
+                0x12, 0x04, 0xF0, 0x90,
+                0xA2, 0x02, 0x60, 0x05, 0x71, 0xFF, 0xD0, 0x12, 0x22, 0x10, 0x12, 0x04,
+                0x00, 0xE0, 0x00, 0xEE,
+            ]);
+        }
+
+        test "compile constants, aliases and register operators" {
+            let source = "
+                :const SPEED 3
+                :alias x v4
+                : main
+                    x := SPEED
+                    x -= 1
+                    x += v2
+                    x -= v2
+                    x =- v2
+                    x <<= x
+                    x := random 0x0F
+                    x := key
+            ";
+
+            let program = compile(source).unwrap();
+
+            assert_eq!(program, [
+                0x12, 0x02,
+                0x64, 0x03, 0x74, 0xFF, 0x84, 0x24, 0x84, 0x25, 0x84, 0x27, 0x84, 0x4E, 0xC4, 0x0F, 0xF4, 0x0A,
+            ]);
+        }
+
+        test "compile conditionals and loops into skips and jumps" {
+            let source = "
+                : main
+                    loop
+                        if v0 == 3 then v1 := 1
+                        if v0 key begin
+                            v2 := 2
+                        else
+                            v2 := 3
+                        end
+                        v0 += 1
+                    while v0 != 8
+                    again
+            ";
+
+            let program = compile(source).unwrap();
+
+            assert_eq!(program, [
+                0x12, 0x02,
+                0x40, 0x03, 0x61, 0x01,
+                0xE0, 0x9E, 0x12, 0x0E, 0x62, 0x02, 0x12, 0x10, 0x62, 0x03,
+                0x70, 0x01,
+                0x40, 0x08, 0x12, 0x18,
+                0x12, 0x02,
+            ]);
+        }
+
+        test "compare registers through vf" {
+            let program = compile(": main if v1 > 4 then v2 := 0 if v1 <= v3 then v2 := 1").unwrap();
+
+            assert_eq!(program, [
+                0x12, 0x02,
+                0x6F, 0x04, 0x8F, 0x15, 0x3F, 0x01, 0x62, 0x00,
+                0x8F, 0x30, 0x8F, 0x15, 0x3F, 0x00, 0x62, 0x01,
+            ]);
+        }
+
+        test "compile SUPER-CHIP and XO-CHIP statements" {
+            let program = compile(": main hires scroll-down 4 i := long data save v1 - v3 plane 2 : data").unwrap();
+
+            assert_eq!(program, [0x12, 0x02, 0x00, 0xFF, 0x00, 0xC4, 0xF0, 0x00, 0x02, 0x0E, 0x51, 0x32, 0xF2, 0x01]);
+        }
+
+        test "report lines with errors" {
+            let error = compile(": main\n  v0 := 1\n  v0 ?= 2").unwrap_err();
+
+            assert!(error.starts_with("Line 3:"), "{}", error);
+        }
+
+        test "reject programs without main or with undefined labels" {
+            assert!(compile(": start clear").is_err());
+            assert!(compile(": main jump nowhere").is_err());
+        }
+
+        test "reject unsupported directives" {
+            let error = compile(":macro twice { clear clear } : main").unwrap_err();
+
+            assert!(error.contains(":macro"), "{}", error);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use serde::Deserialize;
+use super::constants::PROGRAM_ADDRESS_START;
+
+/// Machine which a program was written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    Chip8,
+    SuperChip,
+    XoChip,
+}
+
+impl Platform {
+    /// Guess the platform from instructions which only exist on later platforms. Only
+    /// instructions reachable from the start of the program count, so that data is not
+    /// mistaken for code.
+    pub fn detect(program: &[u8]) -> Self {
+        if program.len() > Platform::Chip8.program_size_max() {
+            return Platform::XoChip;
+        }
+
+        let opcodes = reachable_opcodes(program);
+
+        let is_xo_chip = opcodes.iter().any(|&opcode| {
+            opcode == 0xF000                      // i := long NNNN
+                || opcode == 0xF002               // audio
+                || opcode & 0xF00E == 0x5002      // save/load vx - vy
+        });
+        let is_super_chip = opcodes.iter().any(|&opcode| {
+            opcode == 0x00FE || opcode == 0x00FF  // lores, hires
+                || opcode == 0x00FB || opcode == 0x00FC  // scroll right, left
+        });
+
+        if is_xo_chip {
+            Platform::XoChip
+        } else if is_super_chip {
+            Platform::SuperChip
+        } else {
+            Platform::Chip8
+        }
+    }
+
+    /// Number of bytes which a program can take up, from where programs are loaded to the
+    /// end of memory: 4KB on CHIP-8 and SUPER-CHIP, and 64KB on XO-CHIP.
+    pub fn program_size_max(&self) -> usize {
+        let memory_size = match self {
+            Platform::Chip8 | Platform::SuperChip => 0x1000,
+            Platform::XoChip                      => 0x10000,
+        };
+        memory_size - PROGRAM_ADDRESS_START
+    }
+}
+
+// Follow jumps, calls and skips from the first instruction. Computed jumps (Bnnn) cannot
+// be followed, so code which is only reached through them is missed.
+fn reachable_opcodes(program: &[u8]) -> Vec<u16> {
+    let opcode_at = |offset: usize| program.get(offset..offset + 2)
+        .map(|bytes| (bytes[0] as u16) << 8 | bytes[1] as u16);
+
+    let mut visited = BTreeSet::new();
+    let mut pending = vec![0];
+    let mut opcodes = Vec::new();
+
+    while let Some(offset) = pending.pop() {
+        let opcode = match opcode_at(offset) {
+            Some(opcode) if visited.insert(offset) => opcode,
+            _ => continue,
+        };
+        opcodes.push(opcode);
+
+        let target = (opcode & 0x0FFF) as usize;
+        match opcode & 0xF000 {
+            0x0000 if opcode == 0x00EE || opcode == 0x00FD => {},
+            0x1000 => pending.extend(target.checked_sub(PROGRAM_ADDRESS_START)),
+            0x2000 => {
+                pending.extend(target.checked_sub(PROGRAM_ADDRESS_START));
+                pending.push(offset + 2);
+            },
+            0x3000 | 0x4000 | 0x5000 | 0x9000 | 0xE000 => {
+                pending.push(offset + 2);
+                pending.push(offset + 4);
+            },
+            0xB000 => {},
+            0xF000 if opcode == 0xF000 => pending.push(offset + 4),
+            _ => pending.push(offset + 2),
+        }
+    }
+
+    opcodes
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Platform::Chip8     => "CHIP-8",
+            Platform::SuperChip => "SUPER-CHIP",
+            Platform::XoChip    => "XO-CHIP",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+#[path = "./platform_test.rs"]
+mod platform_test;
@@ -0,0 +1,34 @@
+#![cfg(test)]
+use speculate::speculate;
+use std::env::current_dir;
+use crate::built_in::BUILT_IN_PROGRAM_NAMES;
+use crate::chip8::io::filesystem::Filesystem;
+
+use super::*;
+
+speculate! {
+    describe "detect" {
+        test "detect built-in programs as CHIP-8" {
+            let filesystem = Filesystem::new(current_dir().unwrap());
+
+            for name in BUILT_IN_PROGRAM_NAMES.iter() {
+                let program = filesystem.load_built_in_program(name).unwrap();
+
+                assert_eq!(Platform::detect(&program.rom), Platform::Chip8, "program {}", name);
+            }
+        }
+
+        test "detect SUPER-CHIP by switching to high resolution" {
+            assert_eq!(Platform::detect(&[0x00, 0xE0, 0x00, 0xFF]), Platform::SuperChip);
+        }
+
+        test "detect XO-CHIP by long jumps and audio" {
+            assert_eq!(Platform::detect(&[0x00, 0xFF, 0xF0, 0x00, 0x12, 0x34]), Platform::XoChip);
+            assert_eq!(Platform::detect(&[0xF0, 0x02]), Platform::XoChip);
+        }
+
+        test "detect XO-CHIP by size" {
+            assert_eq!(Platform::detect(&[0; 4000]), Platform::XoChip);
+        }
+    }
+}
@@ -0,0 +1,107 @@
+pub const FAST_FORWARD_DEFAULT: &str = "uncapped";
+
+// Slow motion runs a frame for every this many frames of normal speed.
+const SLOW_MOTION_FACTOR: u32 = 4;
+
+/// How fast programs run while fast-forwarding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FastForward {
+    /// As fast as the host can run them.
+    Uncapped,
+    /// A multiple of normal speed.
+    Times(u32),
+}
+
+impl FastForward {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if value == "uncapped" {
+            return Ok(FastForward::Uncapped);
+        }
+
+        value.trim_end_matches('x').parse::<u32>().ok()
+            .filter(|&times| times >= 2)
+            .map(FastForward::Times)
+            .ok_or_else(|| format!("Fast-forward speed must be 'uncapped' or a whole number of at least 2, but is '{}'.", value))
+    }
+}
+
+/// Pausing, advancing single frames, fast-forwarding and slow motion, in 60Hz frames.
+pub struct Speed {
+    fast_forward: FastForward,
+    is_paused: bool,
+    frames_to_advance: u32,
+    is_fast_forwarding: bool,
+    is_slow_motion: bool,
+    frame_count: u64,
+}
+
+impl Speed {
+    pub fn new(fast_forward: FastForward) -> Self {
+        Speed {
+            fast_forward,
+            is_paused: false,
+            frames_to_advance: 0,
+            is_fast_forwarding: false,
+            is_slow_motion: false,
+            frame_count: 0,
+        }
+    }
+
+    pub fn toggle_pause(&mut self) -> bool {
+        self.is_paused = !self.is_paused;
+        self.frames_to_advance = 0;
+        self.is_paused
+    }
+
+    /// Run a single frame while paused, or pause if running.
+    pub fn advance_frame(&mut self) {
+        if self.is_paused {
+            self.frames_to_advance += 1;
+        } else {
+            self.is_paused = true;
+        }
+    }
+
+    pub fn set_fast_forwarding(&mut self, is_fast_forwarding: bool) {
+        self.is_fast_forwarding = is_fast_forwarding;
+    }
+
+    pub fn toggle_slow_motion(&mut self) -> bool {
+        self.is_slow_motion = !self.is_slow_motion;
+        self.is_slow_motion
+    }
+
+    /// Start a frame. Returns whether the program runs in it.
+    pub fn begin_frame(&mut self) -> bool {
+        if self.is_paused {
+            if self.frames_to_advance == 0 {
+                return false;
+            }
+            self.frames_to_advance -= 1;
+        }
+
+        self.frame_count += 1;
+        true
+    }
+
+    /// Number of 60Hz clock ticks to wait for after the frame.
+    pub fn clock_ticks(&self) -> u32 {
+        if self.is_paused {
+            return 1;
+        }
+
+        match (self.is_fast_forwarding, self.fast_forward) {
+            (true, FastForward::Uncapped)     => 0,
+            (true, FastForward::Times(times)) => match self.frame_count % times as u64 {
+                0 => 1,
+                _ => 0,
+            },
+            (false, _) if self.is_slow_motion => SLOW_MOTION_FACTOR,
+            (false, _)                        => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "./speed_test.rs"]
+mod speed_test;
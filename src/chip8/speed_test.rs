@@ -0,0 +1,74 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "fast forward" {
+        test "parse uncapped and multiples of normal speed" {
+            assert_eq!(FastForward::parse("uncapped"), Ok(FastForward::Uncapped));
+            assert_eq!(FastForward::parse("4"), Ok(FastForward::Times(4)));
+            assert_eq!(FastForward::parse("8x"), Ok(FastForward::Times(8)));
+        }
+
+        test "reject speeds which are not faster" {
+            assert!(FastForward::parse("1").is_err());
+            assert!(FastForward::parse("fast").is_err());
+        }
+    }
+
+    describe "speed" {
+        test "run frames at normal speed" {
+            let mut speed = Speed::new(FastForward::Times(3));
+
+            assert!(speed.begin_frame());
+            assert_eq!(speed.clock_ticks(), 1);
+        }
+
+        test "run no frames while paused" {
+            let mut speed = Speed::new(FastForward::Times(3));
+            speed.toggle_pause();
+
+            assert!(!speed.begin_frame());
+            assert_eq!(speed.clock_ticks(), 1);
+        }
+
+        test "run a single frame when advancing while paused" {
+            let mut speed = Speed::new(FastForward::Times(3));
+
+            speed.advance_frame();
+            assert!(!speed.begin_frame());
+
+            speed.advance_frame();
+            assert!(speed.begin_frame());
+            assert!(!speed.begin_frame());
+        }
+
+        test "wait for a clock tick every few frames when fast-forwarding" {
+            let mut speed = Speed::new(FastForward::Times(3));
+            speed.set_fast_forwarding(true);
+
+            let ticks: Vec<u32> = (0..6)
+                .map(|_| { speed.begin_frame(); speed.clock_ticks() })
+                .collect();
+
+            assert_eq!(ticks, [0, 0, 1, 0, 0, 1]);
+        }
+
+        test "not wait when fast-forwarding uncapped" {
+            let mut speed = Speed::new(FastForward::Uncapped);
+            speed.set_fast_forwarding(true);
+
+            speed.begin_frame();
+            assert_eq!(speed.clock_ticks(), 0);
+        }
+
+        test "wait for several clock ticks in slow motion" {
+            let mut speed = Speed::new(FastForward::Times(3));
+            speed.toggle_slow_motion();
+
+            speed.begin_frame();
+            assert_eq!(speed.clock_ticks(), SLOW_MOTION_FACTOR);
+        }
+    }
+}
@@ -1,53 +1,243 @@
 use fps_clock::FpsClock;
 
-use super::io::display::Display;
-use super::io::keypad::{Keypad, KeypadState};
-use super::io::sound::Sound;
+use super::io::frontend::{Buzzer, Controls, Screen};
+use super::io::keypad::{Hotkey, KeypadState};
+use super::io::palette::Palettes;
+use super::io::recorder::Recorder;
+use super::io::screenshot::Screenshots;
+use super::io::watcher::ProgramWatcher;
 use super::cpu::Processor;
-use super::interface::{Input, Output};
+use super::platform::Platform;
+use super::speed::{FastForward, Speed};
+use super::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+use super::interface::{Input, KeyEvent, Output};
 
 const FREQUENCY: u32 = 60;
-const CPU_SPEED_FACTOR: u32 = 9; // 540 Hz
+pub const CPU_SPEED_FACTOR: u32 = 9; // 540 Hz
 
-pub struct System {
+/// Why the run loop stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    Quit,
+    OpenLauncher,
+}
+
+pub struct System<D: Screen, K: Controls, S: Buzzer> {
     clock: FpsClock,
-    display: Display,
-    keypad: Keypad,
-    sound: Sound,
+    display: D,
+    keypad: K,
+    sound: S,
     processor: Processor,
+    palettes: Palettes,
+    screenshots: Screenshots,
+    recorder: Recorder,
+    speed: Speed,
+    instructions_per_frame: u32,
+    watcher: Option<ProgramWatcher>,
+    pixels: [[bool; CHIP8_WIDTH]; CHIP8_HEIGHT],
+    // CHIP-8 keys which went down or up while paused, passed to the program once it runs.
+    paused_key_events: Vec<KeyEvent>,
 }
 
-impl System {
-    pub fn new(display: Display, keypad: Keypad, sound: Sound, processor: Processor) -> Self {
+impl<D: Screen, K: Controls, S: Buzzer> System<D, K, S> {
+    pub fn new(display: D, keypad: K, sound: S, processor: Processor, palettes: Palettes, screenshots: Screenshots, recorder: Recorder) -> Self {
         System {
             clock: FpsClock::new(FREQUENCY),
             display,
             keypad,
             sound,
             processor,
+            palettes,
+            screenshots,
+            recorder,
+            speed: Speed::new(FastForward::Uncapped),
+            instructions_per_frame: CPU_SPEED_FACTOR,
+            watcher: None,
+            pixels: [[false; CHIP8_WIDTH]; CHIP8_HEIGHT],
+            paused_key_events: Vec::new(),
         }
     }
 
-    pub fn run_loop(&mut self) {
-        'main: loop {
-            for _ in 0..CPU_SPEED_FACTOR {
-                let pressed_keycodes = &(match self.keypad.state() {
-                    KeypadState::PressedEscape       => break 'main,
-                    KeypadState::PressedKeycodes(pk) => pk
-                });
+    pub fn with_fast_forward(mut self, fast_forward: FastForward) -> Self {
+        self.speed = Speed::new(fast_forward);
+        self
+    }
+
+    /// Number of instructions executed in each 60Hz frame.
+    pub fn with_speed(mut self, instructions_per_frame: u32) -> Self {
+        self.instructions_per_frame = instructions_per_frame;
+        self
+    }
+
+    /// Load the program again whenever its file changes.
+    pub fn with_program_watcher(mut self, watcher: Option<ProgramWatcher>) -> Self {
+        self.watcher = watcher;
+        self
+    }
+
+    pub fn run_loop(&mut self) -> Exit {
+        let exit = 'main: loop {
+            self.reload_changed_program();
+
+            let is_running = self.speed.begin_frame();
+            let mut sound_states = Vec::with_capacity(self.instructions_per_frame as usize);
+
+            for _ in 0..self.instructions_per_frame {
+                let mut key_events = match self.keypad.state() {
+                    KeypadState::PressedEscape                => break 'main Exit::Quit,
+                    KeypadState::Hotkey(Hotkey::OpenLauncher) => break 'main Exit::OpenLauncher,
+                    KeypadState::Hotkey(hk)                   => { self.handle_hotkey(hk); continue },
+                    KeypadState::WindowChanged                => { self.render(); continue },
+                    KeypadState::Pointer { x, y, is_pressed } => self.pointer_key_events(x, y, is_pressed),
+                    KeypadState::KeyEvents(events)            => events,
+                };
+
+                if !is_running {
+                    self.paused_key_events.append(&mut key_events);
+                    continue;
+                }
+                if !self.paused_key_events.is_empty() {
+                    key_events.splice(0..0, self.paused_key_events.drain(..));
+                }
 
-                let input = Input { pressed_keycodes };
+                let input = Input { key_events: &key_events };
                 let Output { is_sound_on, pixels, have_pixels_changed } = self.processor.execute_instruction(input);
 
-                self.sound.set(is_sound_on);
+                sound_states.push(is_sound_on);
 
                 if have_pixels_changed {
-                    self.display.render(&pixels).expect("Cannot render pixels on display");
+                    self.pixels = pixels;
+                    self.render();
                 }
             }
 
-            self.processor.update_timers();
-            self.clock.tick();
+            if is_running {
+                self.end_frame(&sound_states);
+            }
+
+            for _ in 0..self.speed.clock_ticks() {
+                self.clock.tick();
+            }
+        };
+
+        let screenshot = self.screenshots.take_on_exit(&self.pixels, self.palettes.current(), self.display.pixel_scale());
+        match screenshot {
+            Ok(Some(path)) => info!("Saved screenshot: {:?}", path),
+            Ok(None)       => {},
+            Err(e)         => error!("Cannot save screenshot: {}", e),
         }
+
+        self.recorder.stop();
+
+        exit
+    }
+
+    pub fn display(&self) -> &D {
+        &self.display
+    }
+
+    /// Start the program over, with a blank screen.
+    pub fn reset(&mut self) {
+        self.processor.reset();
+        self.pixels = [[false; CHIP8_WIDTH]; CHIP8_HEIGHT];
+        self.paused_key_events.clear();
+        self.render();
+    }
+
+    fn reload_changed_program(&mut self) {
+        let program = match self.watcher.as_mut().and_then(ProgramWatcher::poll) {
+            Some(program) => program,
+            None          => return,
+        };
+
+        let load_address = self.processor.load_address();
+        match program.and_then(|program| program.check_size_at(Platform::Chip8, load_address).map(|_| program)) {
+            Ok(program) => {
+                info!("Program file changed, reloaded: name={}, size={}B", program.name, program.rom.len());
+                self.processor.load_program(&program.rom);
+                self.reset();
+            },
+            Err(e) => error!("Cannot reload program: {}", e),
+        }
+    }
+
+    fn end_frame(&mut self, sound_states: &[bool]) {
+        if let Some(events) = self.processor.key_history().last().filter(|events| !events.is_empty()) {
+            trace!("Key events in frame: {:?}", events);
+        }
+
+        self.processor.update_timers();
+        self.sound.play_frame(sound_states);
+
+        if self.display.set_pressed_keycodes(&self.processor.pressed_keycodes()) {
+            self.render();
+        }
+        self.recorder.capture(&self.pixels, self.palettes.current(), sound_states);
+    }
+
+    fn handle_hotkey(&mut self, hotkey: Hotkey) {
+        match hotkey {
+            Hotkey::CyclePalette => {
+                let palette = self.palettes.cycle().clone();
+                self.display.set_palette(palette);
+            },
+            Hotkey::ToggleFullscreen => {
+                self.display.toggle_fullscreen().expect("Cannot toggle fullscreen");
+            },
+            Hotkey::Screenshot => {
+                match self.screenshots.take(&self.pixels, self.palettes.current(), self.display.pixel_scale()) {
+                    Ok(path) => info!("Saved screenshot: {:?}", path),
+                    Err(e)   => error!("Cannot save screenshot: {}", e),
+                }
+            },
+            Hotkey::ToggleKeypadOverlay => {
+                self.display.toggle_keypad_overlay();
+            },
+            Hotkey::ToggleControlsHelp => {
+                self.display.toggle_controls_help();
+            },
+            Hotkey::ToggleMute => {
+                let is_muted = self.sound.toggle_mute();
+                info!("Sound {}", if is_muted { "muted" } else { "unmuted" });
+            },
+            Hotkey::TogglePause => {
+                let is_paused = self.speed.toggle_pause();
+                info!("{}", if is_paused { "Paused" } else { "Resumed" });
+            },
+            Hotkey::AdvanceFrame => {
+                self.speed.advance_frame();
+            },
+            Hotkey::ToggleSlowMotion => {
+                let is_slow_motion = self.speed.toggle_slow_motion();
+                info!("Slow motion {}", if is_slow_motion { "on" } else { "off" });
+            },
+            Hotkey::FastForward { is_held } => {
+                self.speed.set_fast_forwarding(is_held);
+            },
+            Hotkey::Reset => {
+                info!("Reset");
+                self.reset();
+            },
+            Hotkey::ToggleRecording => {
+                self.recorder.toggle(self.palettes.current())
+                    .unwrap_or_else(|e| error!("Cannot start recording: {}", e));
+            },
+            // The run loop stops instead.
+            Hotkey::OpenLauncher => {},
+        }
+
+        self.render();
+    }
+
+    // The keypad keeps track of the key held with the pointer, along with keyboard keys
+    // and gamepad inputs mapped to the same CHIP-8 key.
+    fn pointer_key_events(&mut self, x: i32, y: i32, is_pressed: bool) -> Vec<KeyEvent> {
+        let chip8_keycode = if is_pressed { self.display.keycode_at(x, y) } else { None };
+
+        self.keypad.set_pointer_keycode(chip8_keycode)
+    }
+
+    fn render(&mut self) {
+        self.display.render(&self.pixels).expect("Cannot render pixels on display");
     }
 }
@@ -0,0 +1,141 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use super::chip8::assembler::assemble;
+use super::chip8::cpu::Processor;
+use super::chip8::disassembler::disassemble;
+use super::chip8::interface::Input;
+use super::chip8::io::filesystem::{Filesystem, Program, STDIO_PATH};
+use super::chip8::platform::Platform;
+use super::chip8::system::CPU_SPEED_FACTOR;
+use super::args::ProgramArg;
+use super::built_in::BUILT_IN_PROGRAMS;
+
+// Subcommands other than `run`, which print their results to standard output.
+
+pub fn info(program: ProgramArg) -> Result<(), String> {
+    let program = Filesystem::at_current_dir()?.load_program(program)?;
+
+    let detected = Platform::detect(&program.rom);
+
+    println!("Name:     {}", program.name);
+    println!("Size:     {} bytes", program.rom.len());
+    println!("SHA-1:    {}", program.sha1());
+
+    match program.info {
+        Some(info) => {
+            println!("Title:    {}", info.title);
+            if let Some(description) = info.description {
+                println!("About:    {}", description);
+            }
+            if !info.authors.is_empty() {
+                println!("Authors:  {}", info.authors.join(", "));
+            }
+            if let Some(year) = info.year {
+                println!("Year:     {}", year);
+            }
+            println!("Platform: {}", info.platform.unwrap_or(detected));
+            if let Some(controls) = info.controls {
+                println!("Controls: {}", controls);
+            }
+        },
+        None => {
+            println!("Platform: {} (detected, not in the program database)", detected);
+        },
+    }
+
+    Ok(())
+}
+
+pub fn list(path: Option<String>) -> Result<(), String> {
+    match path {
+        Some(path) => {
+            for program_file in Filesystem::at_current_dir()?.list_program_files(&path)? {
+                println!("{}", program_file);
+            }
+        },
+        None => {
+            for program in BUILT_IN_PROGRAMS.iter() {
+                let year = program.year.map(|year| year.to_string());
+                let mut credits = program.authors.to_vec();
+                credits.extend(year.as_deref());
+                let credits = if credits.is_empty() { String::new() } else { format!(" ({})", credits.join(", ")) };
+
+                println!("{:<10}{}{}", program.name, program.title, credits);
+                if !program.description.is_empty() {
+                    println!("{:<10}{}", "", program.description);
+                }
+                if !program.controls.is_empty() {
+                    println!("{:<10}Controls: {}", "", program.controls);
+                }
+                if let Some(speed) = program.speed {
+                    println!("{:<10}Speed: {} instructions per frame", "", speed);
+                }
+            }
+        },
+    }
+
+    Ok(())
+}
+
+pub fn disasm(program: ProgramArg, output: Option<PathBuf>, load_address: usize) -> Result<(), String> {
+    let Program { rom, .. } = Filesystem::at_current_dir()?.load_program(program)?;
+    let listing = disassemble(&rom, load_address).join("\n") + "\n";
+
+    match output {
+        Some(path) => fs::write(&path, listing)
+            .map_err(|e| format!("Cannot write listing to {:?}: {}", path, e)),
+        None => {
+            print!("{}", listing);
+            Ok(())
+        },
+    }
+}
+
+pub fn asm(source: PathBuf, output: PathBuf, load_address: usize) -> Result<(), String> {
+    let text = fs::read_to_string(&source)
+        .map_err(|e| format!("Cannot read source file {:?}: {}", source, e))?;
+
+    let program = assemble(&text, load_address)?;
+
+    // The program alone goes to standard output, so that it can be piped into `run`.
+    if output == Path::new(STDIO_PATH) {
+        return io::stdout().write_all(&program)
+            .map_err(|e| format!("Cannot write program to standard output: {}", e));
+    }
+
+    fs::write(&output, &program)
+        .map_err(|e| format!("Cannot write program to {:?}: {}", output, e))?;
+
+    println!("Assembled {} bytes into {:?}", program.len(), output);
+    Ok(())
+}
+
+pub fn bench(program: ProgramArg, frames: u32, load_address: usize) -> Result<(), String> {
+    let program = Filesystem::at_current_dir()?.load_program(program)?;
+    program.check_size_at(Platform::Chip8, load_address)?;
+
+    let mut processor = Processor::new().with_load_address(load_address);
+    processor.load_program(&program.rom);
+
+    let start = Instant::now();
+    for _ in 0..frames {
+        for _ in 0..CPU_SPEED_FACTOR {
+            processor.execute_instruction(Input { key_events: &[] });
+        }
+        processor.update_timers();
+    }
+    let seconds = start.elapsed().as_secs_f64();
+
+    let instructions = frames as u64 * CPU_SPEED_FACTOR as u64;
+    let emulated_seconds = frames as f64 / 60.0;
+
+    println!("Program:      {}", program.name);
+    println!("Frames:       {} ({} instructions)", frames, instructions);
+    println!("Time:         {:.3} s", seconds);
+    println!("Instructions: {:.0} per second", instructions as f64 / seconds);
+    println!("Speed:        {:.1}x normal speed", emulated_seconds / seconds);
+
+    Ok(())
+}
@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+
+use super::constants::APP_DIR;
+use super::chip8::io::database::ProgramInfo;
+use super::chip8::io::filesystem::Program;
+use super::chip8::io::gamepad::GamepadMap;
+use super::chip8::io::keymap::Keymap;
+use super::chip8::io::palette::Palette;
+
+const CONFIG_FILE: &str = "config.toml";
+
+/// Settings loaded from a TOML configuration file. Settings in a `[roms.*]` section
+/// override the global ones for a single program, which is picked by its name or by
+/// the SHA-1 hash of its file, and so do settings from the program database.
+///
+/// # Example
+/// ```toml
+/// window_scale = 12
+/// scaling = "fit"
+/// palette = "sepia"
+/// speed = 9
+/// fast_forward = "4x"
+/// keymap = "azerty"
+/// quirks = []
+/// program_dir = "roms"
+///
+/// [buzzer]
+/// frequency = 220.0
+/// volume = 0.1
+/// waveform = "triangle"
+/// muted = false
+///
+/// [palettes]
+/// sepia = ["#2B1B0E", "#E8D5B0"]
+/// xo = ["#000000", "#FFFFFF", "#FF0000", "#FFFF00"]
+///
+/// [keymaps.arrows]
+/// base = "qwerty"
+/// keys = { Up = "5", Down = "8", Left = "7", Right = "9" }
+///
+/// [gamepads.shoulders]
+/// base = "default"
+/// inputs = { leftshoulder = "4", rightshoulder = "6" }
+///
+/// [roms.pong]
+/// notes = "Left player: 1 and Q, right player: 4 and R."
+/// keymap = "arrows"
+/// gamepad = "shoulders"
+/// quirks = ["key-wait-on-press"]
+///
+/// [roms.b232ef880bd6060fb45fa6effed7edf0ae95670e]
+/// speed = 15
+/// palette = "amber"
+/// buzzer = { muted = true }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub window_scale: Option<u32>,
+    pub scaling: Option<String>,
+    pub palette: Option<String>,
+    pub palettes: BTreeMap<String, Vec<String>>,
+    pub speed: Option<u32>,
+    pub fast_forward: Option<String>,
+    pub buzzer: BuzzerConfig,
+    pub keymap: Option<String>,
+    pub keymaps: BTreeMap<String, KeymapConfig>,
+    pub gamepad: Option<String>,
+    pub gamepads: BTreeMap<String, GamepadConfig>,
+    pub quirks: Option<Vec<String>>,
+    /// Directory with program files which the launcher lists after built-in programs.
+    pub program_dir: Option<String>,
+    pub roms: BTreeMap<String, RomConfig>,
+}
+
+/// Sound of the buzzer, and whether it starts muted.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuzzerConfig {
+    pub frequency: Option<f32>,
+    pub volume: Option<f32>,
+    pub waveform: Option<String>,
+    pub muted: Option<bool>,
+}
+
+/// Custom keymap: key names mapped to CHIP-8 hex digits, on top of a preset keymap.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeymapConfig {
+    pub base: Option<String>,
+    pub keys: BTreeMap<String, String>,
+}
+
+/// Custom gamepad mapping: input names mapped to CHIP-8 hex digits, on top of a preset
+/// mapping.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GamepadConfig {
+    pub base: Option<String>,
+    pub inputs: BTreeMap<String, String>,
+}
+
+/// Settings which override the global ones for a single program, by its name or by
+/// the SHA-1 hash of its file.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RomConfig {
+    /// Shown in the launcher when the program is selected.
+    pub notes: Option<String>,
+    pub window_scale: Option<u32>,
+    pub scaling: Option<String>,
+    pub palette: Option<String>,
+    pub speed: Option<u32>,
+    pub fast_forward: Option<String>,
+    pub buzzer: BuzzerConfig,
+    pub keymap: Option<String>,
+    pub gamepad: Option<String>,
+    pub quirks: Option<Vec<String>>,
+}
+
+impl RomConfig {
+    // Fill in settings which are missing with the ones from a lower layer.
+    fn or(self, lower: RomConfig) -> RomConfig {
+        RomConfig {
+            notes: self.notes.or(lower.notes),
+            window_scale: self.window_scale.or(lower.window_scale),
+            scaling: self.scaling.or(lower.scaling),
+            palette: self.palette.or(lower.palette),
+            speed: self.speed.or(lower.speed),
+            fast_forward: self.fast_forward.or(lower.fast_forward),
+            buzzer: BuzzerConfig {
+                frequency: self.buzzer.frequency.or(lower.buzzer.frequency),
+                volume: self.buzzer.volume.or(lower.buzzer.volume),
+                waveform: self.buzzer.waveform.or(lower.buzzer.waveform),
+                muted: self.buzzer.muted.or(lower.buzzer.muted),
+            },
+            keymap: self.keymap.or(lower.keymap),
+            gamepad: self.gamepad.or(lower.gamepad),
+            quirks: self.quirks.or(lower.quirks),
+        }
+    }
+}
+
+impl Config {
+    /// Load the config file at the path, or otherwise the one in the user's config
+    /// directory if it exists.
+    pub fn find(path: Option<&str>) -> Result<Self, String> {
+        match path {
+            Some(path) => Config::load(Path::new(path)),
+            None => match default_path() {
+                Some(path) if path.is_file() => Config::load(&path),
+                _                             => Ok(Config::default()),
+            },
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read config file {:?}: {}", path, e))?;
+
+        let config: Config = toml::from_str(&contents)
+            .map_err(|e| format!("Cannot parse config file {:?}: {}", path, e))?;
+
+        debug!("Loaded config: path={:?}, config={:?}", path, config);
+
+        Ok(config)
+    }
+
+    pub fn custom_palettes(&self) -> Result<Vec<Palette>, String> {
+        self.palettes.iter()
+            .map(|(name, colors)| Palette::from_hex(name, colors))
+            .collect()
+    }
+
+    pub fn custom_keymaps(&self) -> Result<Vec<Keymap>, String> {
+        self.keymaps.iter()
+            .map(|(name, keymap)| {
+                let base = keymap.base.as_deref()
+                    .map(|base| Keymap::preset(base).ok_or_else(|| format!("Keymap '{}' has unknown base preset '{}'.", name, base)))
+                    .transpose()?;
+
+                Keymap::from_names(name, base.as_ref(), &keymap.keys)
+            })
+            .collect()
+    }
+
+    pub fn custom_gamepad_maps(&self) -> Result<Vec<GamepadMap>, String> {
+        self.gamepads.iter()
+            .map(|(name, gamepad)| {
+                let base = gamepad.base.as_deref()
+                    .map(|base| GamepadMap::preset(base).ok_or_else(|| format!("Gamepad mapping '{}' has unknown base preset '{}'.", name, base)))
+                    .transpose()?;
+
+                GamepadMap::from_names(name, base.as_ref(), &gamepad.inputs)
+            })
+            .collect()
+    }
+
+    /// Settings for the program, from its section by SHA-1 hash, then its section by
+    /// name, then its entry in the program database, then the global settings.
+    pub fn settings_for(&self, program: &Program) -> RomConfig {
+        let section = |key: &str| self.roms.get(key).cloned().unwrap_or_default();
+        let mut settings = section(&program.sha1())
+            .or(section(&program.name))
+            .or(program.info.as_ref().map(recommended_settings).unwrap_or_default());
+
+        // A gamepad preset named after the program is preferred over the global
+        // mapping, since programs use different keys.
+        if settings.gamepad.is_none() && GamepadMap::preset(&program.name).is_some() {
+            settings.gamepad = Some(program.name.clone());
+        }
+
+        settings.or(self.global_settings())
+    }
+
+    fn global_settings(&self) -> RomConfig {
+        RomConfig {
+            notes: None,
+            window_scale: self.window_scale,
+            scaling: self.scaling.clone(),
+            palette: self.palette.clone(),
+            speed: self.speed,
+            fast_forward: self.fast_forward.clone(),
+            buzzer: self.buzzer.clone(),
+            keymap: self.keymap.clone(),
+            gamepad: self.gamepad.clone(),
+            quirks: self.quirks.clone(),
+        }
+    }
+}
+
+// Settings which the program database recommends for the program. Its keys are added
+// to the keymap separately, since they go on top of any keymap.
+fn recommended_settings(info: &ProgramInfo) -> RomConfig {
+    RomConfig {
+        palette: info.colors.as_ref().map(|colors| colors.join(",")),
+        speed: info.speed,
+        quirks: info.quirks.clone(),
+        ..RomConfig::default()
+    }
+}
+
+fn default_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR).join(CONFIG_FILE))
+}
+
+#[cfg(test)]
+#[path = "./config_test.rs"]
+mod config_test;
@@ -0,0 +1,130 @@
+#![cfg(test)]
+use speculate::speculate;
+use std::env::current_dir;
+use std::io::Write;
+use tempfile::NamedTempFile;
+use crate::chip8::io::filesystem::Filesystem;
+
+use super::*;
+
+const PONG_SHA1: &str = "b232ef880bd6060fb45fa6effed7edf0ae95670e";
+
+fn parse(contents: &str) -> Config {
+    toml::from_str(contents).unwrap()
+}
+
+fn load_pong() -> Program {
+    Filesystem::new(current_dir().unwrap()).load_built_in_program("pong").unwrap()
+}
+
+speculate! {
+    describe "settings_for" {
+        test "fall back to the global settings" {
+            let config = parse("
+                palette = 'amber'
+                speed = 12
+                [buzzer]
+                volume = 0.5
+            ");
+
+            let settings = config.settings_for(&load_pong());
+
+            assert_eq!(settings.palette.as_deref(), Some("amber"));
+            assert_eq!(settings.speed, Some(12));
+            assert_eq!(settings.buzzer.volume, Some(0.5));
+            assert_eq!(settings.window_scale, None);
+        }
+
+        test "prefer the section by hash over the section by name over the global settings" {
+            let config = parse(&format!("
+                palette = 'amber'
+                speed = 12
+                window_scale = 8
+                [roms.pong]
+                speed = 15
+                palette = 'green'
+                [roms.{}]
+                palette = 'lcd'
+                buzzer = {{ muted = true }}
+            ", PONG_SHA1));
+
+            let settings = config.settings_for(&load_pong());
+
+            assert_eq!(settings.palette.as_deref(), Some("lcd"));
+            assert_eq!(settings.speed, Some(15));
+            assert_eq!(settings.window_scale, Some(8));
+            assert_eq!(settings.buzzer.muted, Some(true));
+        }
+
+        test "prefer the sections of the program over the program database over the global settings" {
+            let config = parse("
+                speed = 12
+                palette = 'amber'
+                quirks = []
+                [roms.pong]
+                speed = 15
+            ");
+            let mut program = load_pong();
+            program.info = Some(ProgramInfo {
+                speed: Some(20),
+                colors: Some(vec!["#000000".to_owned(), "#FFFFFF".to_owned()]),
+                quirks: Some(vec!["key-wait-on-press".to_owned()]),
+                ..ProgramInfo::default()
+            });
+
+            let settings = config.settings_for(&program);
+
+            assert_eq!(settings.speed, Some(15));
+            assert_eq!(settings.palette.as_deref(), Some("#000000,#FFFFFF"));
+            assert_eq!(settings.quirks, Some(vec!["key-wait-on-press".to_owned()]));
+        }
+
+        test "take the notes from the section of the program only" {
+            let config = parse("
+                program_dir = 'roms'
+                [roms.pong]
+                notes = 'Two players'
+            ");
+
+            assert_eq!(config.settings_for(&load_pong()).notes.as_deref(), Some("Two players"));
+            assert_eq!(config.program_dir.as_deref(), Some("roms"));
+        }
+
+        test "ignore sections of other programs" {
+            let config = parse("
+                [roms.tetris]
+                speed = 20
+            ");
+
+            assert_eq!(config.settings_for(&load_pong()).speed, None);
+        }
+
+        test "prefer the gamepad preset for the program over the global mapping" {
+            let config = parse("gamepad = 'default'");
+
+            let mut program = load_pong();
+            assert_eq!(config.settings_for(&program).gamepad.as_deref(), Some("pong"));
+
+            program.name = "custom".to_owned();
+            assert_eq!(config.settings_for(&program).gamepad.as_deref(), Some("default"));
+        }
+    }
+
+    describe "find" {
+        test "load the config file at the path" {
+            let mut file = NamedTempFile::new().unwrap();
+            file.write_all(b"speed = 30").unwrap();
+
+            let config = Config::find(file.path().to_str()).unwrap();
+
+            assert_eq!(config.speed, Some(30));
+        }
+
+        test "reject unknown settings" {
+            let mut file = NamedTempFile::new().unwrap();
+            file.write_all(b"[roms.pong]\nsped = 30").unwrap();
+
+            assert!(Config::find(file.path().to_str()).is_err());
+        }
+    }
+}
@@ -1,8 +1,17 @@
-pub const BUILT_IN_PROGRAMS: [&str; 23] = [
-    "15puzzle", "blinky", "blitz", "brix", "connect4", "guess",
-    "hidden", "invaders", "kaleid", "maze", "merlin", "missile",
-    "pong", "pong2", "puzzle", "syzygy", "tank", "tetris",
-    "tictac", "ufo", "vbrix", "vers", "wipeoff" 
-];
-
-pub const WINDOW_SCALE_DEFAULT: &str = "10";
+pub const FRONTEND_DEFAULT: &str = "sdl";
+pub const TERMINAL_GLYPHS_DEFAULT: &str = "half-block";
+
+pub const WINDOW_SCALE_DEFAULT: u32 = 10;
+pub const SCALING_DEFAULT: &str = "integer";
+
+pub const LOAD_ADDRESS_DEFAULT: &str = "0x200";
+
+pub const SCREENSHOT_SCALE_DEFAULT: &str = "window";
+pub const RECORD_SCALE_DEFAULT: &str = "4";
+pub const RECORD_SCALE_MAX: u32 = 64;
+
+// One minute of emulated time.
+pub const BENCH_FRAMES_DEFAULT: &str = "3600";
+
+// Name of the directory which holds config and state files.
+pub const APP_DIR: &str = "chip8-interpreter-rust";
@@ -2,13 +2,33 @@
 #[macro_use] extern crate log;
 
 mod args;
+mod built_in;
 mod chip8;
+mod commands;
+mod config;
 mod constants;
 mod run;
+mod state;
 mod util;
 
+use std::process;
+use args::Command;
+
 fn main() {
     env_logger::init();
 
-    run::run().unwrap_or_else(|e| error!("Error: {}", e));
+    let result = Command::parse().and_then(|command| match command {
+        Command::Run(args)                                => run::run(*args),
+        Command::Info { program }                         => commands::info(program),
+        Command::List { path }                            => commands::list(path),
+        Command::Disasm { program, output, load_address } => commands::disasm(program, output, load_address),
+        Command::Asm { source, output, load_address }     => commands::asm(source, output, load_address),
+        Command::Bench { program, frames, load_address }  => commands::bench(program, frames, load_address),
+    });
+
+    // Scripts which run the tools tell failures apart by the exit status.
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        process::exit(1);
+    }
 }
@@ -1,26 +1,335 @@
+use sdl2::Sdl;
 use super::chip8::io::display::Display;
+use super::chip8::io::gamepad::{GamepadMap, GAMEPAD_MAP_DEFAULT};
+use super::chip8::io::keymap::{Keymap, KEYMAP_DEFAULT};
 use super::chip8::io::keypad::Keypad;
+use super::chip8::io::launcher::{Launcher, LauncherEntry};
+use super::chip8::io::launcher_window::LauncherWindow;
 use super::chip8::io::sound::Sound;
-use super::chip8::io::filesystem::{Filesystem, Program};
-use super::chip8::cpu::Processor;
-use super::chip8::system::System;
-use super::args::Args;
+use super::chip8::io::filesystem::{Filesystem, Program, STDIO_PATH};
+use super::chip8::io::overlay::{ControlsHelp, KeypadOverlay};
+use super::chip8::io::palette::{Palette, Palettes, PALETTE_DEFAULT};
+use super::chip8::io::recorder::{Recorder, VideoFormat};
+use super::chip8::io::screenshot::Screenshots;
+use super::chip8::io::terminal::{Glyphs, TerminalBell, TerminalDisplay, TerminalKeypad};
+use super::chip8::io::tone::{Tone, Waveform, TONE_FREQUENCY_DEFAULT, TONE_VOLUME_DEFAULT, WAVEFORM_DEFAULT};
+use super::chip8::io::viewport::Scaling;
+use super::chip8::io::watcher::ProgramWatcher;
+use super::chip8::cpu::{Processor, Quirks};
+use super::chip8::speed::{FastForward, FAST_FORWARD_DEFAULT};
+use super::chip8::platform::Platform;
+use super::chip8::system::{Exit, System, CPU_SPEED_FACTOR};
+use super::chip8::constants::{CHIP8_WIDTH, CHIP8_HEIGHT};
+use super::args::{Args, FrontendArg, ProgramArg};
+use super::config::Config;
+use super::built_in::BUILT_IN_PROGRAM_NAMES;
+use super::constants::{SCALING_DEFAULT, WINDOW_SCALE_DEFAULT};
+use super::state::State;
 
-pub fn run() -> Result<(), String> {
-    let Args { window_scale, program } = Args::parse()?;
+pub fn run(args: Args) -> Result<(), String> {
+    let config = Config::find(args.config.as_deref())?;
 
-    let Program { name, rom, .. } =  Filesystem::at_current_dir()?.load_program(program)?;
+    match args.frontend {
+        FrontendArg::Sdl              => run_sdl(args, &config),
+        FrontendArg::Terminal(glyphs) => {
+            let program = args.program.clone()
+                .ok_or("The launcher needs a window, pick a program with --program or --program-file.")?;
+            let (_, options, session) = load_game(args, &config, program)?;
+            run_terminal(glyphs, options.keymap, options.is_muted, session)
+        },
+    }
+}
 
-    let sdl = sdl2::init()?;
-    let display = Display::new(&sdl, name, window_scale)?;
-    let keypad = Keypad::new(&sdl)?;
-    let sound = Sound::new(&sdl)?;
+// Load the program and resolve the settings to run it with.
+fn load_game(args: Args, config: &Config, program: ProgramArg) -> Result<(String, SdlOptions, Session), String> {
+    let Args { load_address, window_scale, scaling, keypad_overlay, speed, fast_forward, quirks, palette, keymap, gamepad, buzzer_frequency, buzzer_volume, buzzer_waveform, mute, screenshot_scale, screenshot_on_exit, record_gif, record_y4m, record_audio, record_scale, .. } = args;
+
+    let watcher = match &program {
+        ProgramArg::ProgramFile(path) if path != STDIO_PATH => Some(ProgramWatcher::new(Filesystem::at_current_dir()?, path.clone())),
+        _                                                   => None,
+    };
+    let program = Filesystem::at_current_dir()?.load_program(program)?;
+
+    let platform = program.info.as_ref()
+        .and_then(|info| info.platform)
+        .unwrap_or_else(|| Platform::detect(&program.rom));
+    if platform != Platform::Chip8 {
+        warn!("Program {} is written for {}, which is not supported, so it may not run correctly.", program.name, platform);
+    }
+    program.check_size_at(Platform::Chip8, load_address)?;
+
+    // Arguments take precedence over the settings for the program from the config file
+    // and the program database, which take precedence over the defaults.
+    let settings = config.settings_for(&program);
+    debug!("Settings for the program: {:?}", settings);
+
+    let window_scale = window_scale.or(settings.window_scale);
+    let scaling = match scaling {
+        Some(scaling) => scaling,
+        None          => Scaling::parse(settings.scaling.as_deref().unwrap_or(SCALING_DEFAULT))?,
+    };
+
+    let custom_palettes = config.custom_palettes()?;
+    let palette = palette.or(settings.palette)
+        .unwrap_or_else(|| PALETTE_DEFAULT.to_owned());
+    let palette = Palette::parse(&palette, &custom_palettes)?;
+    let palettes = Palettes::new(palette, custom_palettes);
+
+    let speed = speed.or(settings.speed).unwrap_or(CPU_SPEED_FACTOR);
+    if speed == 0 {
+        return Err("Speed must be at least 1 instruction per frame.".to_owned());
+    }
+    let fast_forward = match fast_forward {
+        Some(fast_forward) => fast_forward,
+        None               => FastForward::parse(settings.fast_forward.as_deref().unwrap_or(FAST_FORWARD_DEFAULT))?,
+    };
+
+    let waveform = buzzer_waveform.or(settings.buzzer.waveform)
+        .unwrap_or_else(|| WAVEFORM_DEFAULT.to_owned());
+    let tone = Tone::new(
+        buzzer_frequency.or(settings.buzzer.frequency).unwrap_or(TONE_FREQUENCY_DEFAULT),
+        buzzer_volume.or(settings.buzzer.volume).unwrap_or(TONE_VOLUME_DEFAULT),
+        Waveform::parse(&waveform)?,
+    )?;
+    let is_muted = mute || settings.buzzer.muted.unwrap_or(false);
+
+    let custom_keymaps = config.custom_keymaps()?;
+    let is_keymap_given = keymap.is_some();
+    let keymap = keymap.or(settings.keymap)
+        .unwrap_or_else(|| KEYMAP_DEFAULT.to_owned());
+    let mut keymap = Keymap::parse(&keymap, &custom_keymaps)?;
+    if let Some(info) = program.info.as_ref().filter(|_| !is_keymap_given) {
+        keymap = Keymap::from_names(&keymap.name, Some(&keymap), &info.keys)?;
+    }
+
+    let info = program.info.clone().unwrap_or_default();
+    let controls_help = ControlsHelp::new(&info.actions, info.controls.as_deref(), &keymap)?;
+
+    let custom_gamepad_maps = config.custom_gamepad_maps()?;
+    let gamepad_map = gamepad.or(settings.gamepad)
+        .unwrap_or_else(|| GAMEPAD_MAP_DEFAULT.to_owned());
+    let gamepad_map = GamepadMap::parse(&gamepad_map, &custom_gamepad_maps)?;
+
+    let quirks = Quirks::parse(&quirks.or(settings.quirks).unwrap_or_default())?;
+
+    let title = program.info.as_ref()
+        .map(|info| info.title.clone())
+        .unwrap_or_else(|| program.name.clone());
+    let Program { name, rom, .. } = program;
 
-    let mut processor = Processor::new();
+    let mut processor = Processor::new().with_quirks(quirks).with_load_address(load_address);
     processor.load_program(&rom);
 
-    let mut system = System::new(display, keypad, sound, processor);
+    let screenshots = Screenshots::new(name.clone(), screenshot_scale, screenshot_on_exit);
+
+    let mut recorder = Recorder::new(name.clone(), record_scale);
+    if let Some(path) = record_gif {
+        recorder.start(VideoFormat::Gif, path, palettes.current())?;
+    }
+    if let Some(path) = record_y4m {
+        recorder.start(VideoFormat::Y4m, path, palettes.current())?;
+    }
+    if let Some(path) = record_audio {
+        recorder.start_audio(path, tone)?;
+    }
+
+    let options = SdlOptions { window_scale, scaling, keypad_overlay, keymap, controls_help, gamepad_map, tone, is_muted };
+    let session = Session { processor, palettes, screenshots, recorder, speed, fast_forward, watcher };
+
+    Ok((title, options, session))
+}
+
+// Run the program from the arguments, or otherwise the one picked in the launcher, until
+// the window is closed. F9 stops the program and shows the launcher again.
+fn run_sdl(mut args: Args, config: &Config) -> Result<(), String> {
+    let sdl = sdl2::init()?;
+    let mut state = State::load();
+    let mut launcher: Option<Launcher> = None;
+    let mut next_program = args.program.clone();
+
+    loop {
+        let program = match next_program.take() {
+            Some(program) => program,
+            None => {
+                let launcher = launcher.get_or_insert_with(|| Launcher::new(launcher_entries(config)));
+                match pick_program(&sdl, &mut state, &args, config, launcher)? {
+                    Some(program) => program,
+                    None          => break,
+                }
+            },
+        };
+
+        // Programs picked in the launcher which fail to load are reported, and another
+        // one can be picked.
+        let (title, options, session) = match load_game(args.clone(), config, program.clone()) {
+            Ok(game)                     => game,
+            Err(e) if launcher.is_some() => {
+                error!("Error: {}", e);
+                continue;
+            },
+            Err(e)                       => return Err(e),
+        };
+
+        // Recordings and the exit screenshot are only taken of the first program, so that
+        // programs picked in the launcher later do not overwrite its files.
+        args.record_gif = None;
+        args.record_y4m = None;
+        args.record_audio = None;
+        args.screenshot_on_exit = None;
+
+        match run_window(&sdl, &mut state, title, options, session)? {
+            Exit::Quit         => break,
+            Exit::OpenLauncher => {
+                launcher.get_or_insert_with(|| Launcher::new(launcher_entries(config)))
+                    .select_program(&program);
+            },
+        }
+    }
+
+    state.save().unwrap_or_else(|e| warn!("Cannot save state: {}", e));
+
+    Ok(())
+}
+
+// Settings which only the SDL frontend uses.
+struct SdlOptions {
+    window_scale: Option<u32>,
+    scaling: Scaling,
+    keypad_overlay: bool,
+    keymap: Keymap,
+    controls_help: ControlsHelp,
+    gamepad_map: GamepadMap,
+    tone: Tone,
+    is_muted: bool,
+}
+
+// Parts of the system which are the same for every frontend.
+struct Session {
+    processor: Processor,
+    palettes: Palettes,
+    screenshots: Screenshots,
+    recorder: Recorder,
+    speed: u32,
+    fast_forward: FastForward,
+    watcher: Option<ProgramWatcher>,
+}
+
+fn run_window(sdl: &Sdl, state: &mut State, title: String, options: SdlOptions, session: Session) -> Result<Exit, String> {
+    let SdlOptions { window_scale, scaling, keypad_overlay, keymap, controls_help, gamepad_map, tone, is_muted } = options;
+
+    let window_size = window_scale
+        .map(scaled_window_size)
+        .or(state.window_size)
+        .unwrap_or_else(|| scaled_window_size(WINDOW_SCALE_DEFAULT));
+    let key_labels = (0..16)
+        .map(|chip8_keycode| keymap.keycodes(chip8_keycode).first().map(|keycode| keycode.name()).unwrap_or_default())
+        .collect();
+    let overlay = KeypadOverlay::new(key_labels, keypad_overlay);
+    let display = Display::new(sdl, title, window_size, scaling, session.palettes.current().clone(), overlay, controls_help)?;
+    let keypad = Keypad::new(sdl, keymap, gamepad_map)?;
+    let sound = Sound::new(sdl, tone, is_muted)?;
+
+    let mut system = System::new(display, keypad, sound, session.processor, session.palettes, session.screenshots, session.recorder)
+        .with_speed(session.speed)
+        .with_fast_forward(session.fast_forward)
+        .with_program_watcher(session.watcher);
+    let exit = system.run_loop();
+
+    state.window_size = Some(system.display().windowed_size());
+
+    Ok(exit)
+}
+
+// Show the launcher in its own window until a program is picked, or the window is closed.
+fn pick_program(sdl: &Sdl, state: &mut State, args: &Args, config: &Config, launcher: &mut Launcher) -> Result<Option<ProgramArg>, String> {
+    let custom_palettes = config.custom_palettes()?;
+    let palette = args.palette.clone().or_else(|| config.palette.clone())
+        .unwrap_or_else(|| PALETTE_DEFAULT.to_owned());
+    let palette = Palette::parse(&palette, &custom_palettes)?;
+    let window_size = state.window_size
+        .unwrap_or_else(|| scaled_window_size(WINDOW_SCALE_DEFAULT));
+
+    let mut window = LauncherWindow::new(sdl, window_size, palette)?;
+    let picked = window.pick(launcher)?;
+    state.window_size = Some(window.size());
+
+    Ok(picked.map(|index| launcher.entries()[index].program.clone()))
+}
+
+// Built-in programs, then program files in the program directory from the config file.
+fn launcher_entries(config: &Config) -> Vec<LauncherEntry> {
+    let filesystem = match Filesystem::at_current_dir() {
+        Ok(filesystem) => filesystem,
+        Err(e)         => {
+            error!("Cannot list programs: {}", e);
+            return Vec::new();
+        },
+    };
+
+    let mut programs = BUILT_IN_PROGRAM_NAMES.iter()
+        .map(|&name| ProgramArg::BuiltInProgram(name.to_owned()))
+        .collect::<Vec<_>>();
+    if let Some(dir) = &config.program_dir {
+        match filesystem.list_program_files(dir) {
+            Ok(paths) => programs.extend(paths.into_iter().map(ProgramArg::ProgramFile)),
+            Err(e)    => warn!("Cannot list programs in the program directory: {}", e),
+        }
+    }
+
+    programs.into_iter()
+        .filter_map(|arg| match filesystem.load_program(arg.clone()) {
+            Ok(program) => Some(launcher_entry(config, arg, program)),
+            Err(e)      => {
+                warn!("Cannot load program for the launcher: {}", e);
+                None
+            },
+        })
+        .collect()
+}
+
+fn launcher_entry(config: &Config, arg: ProgramArg, program: Program) -> LauncherEntry {
+    let settings = config.settings_for(&program);
+
+    // Description, authors and controls from the program database.
+    let mut description = Vec::new();
+    if let Some(info) = &program.info {
+        description.extend(info.description.clone());
+        if !info.authors.is_empty() {
+            let year = info.year.map(|year| format!(", {}", year)).unwrap_or_default();
+            description.push(format!("By {}{}", info.authors.join(", "), year));
+        }
+        description.extend(info.controls.as_ref().map(|controls| format!("Controls: {}", controls)));
+    }
+
+    let title = program.info.as_ref()
+        .map(|info| info.title.clone())
+        .filter(|title| !title.is_empty())
+        .unwrap_or_else(|| program.name.clone());
+
+    LauncherEntry {
+        program: arg,
+        title,
+        description: description.join("\n"),
+        notes: settings.notes,
+        speed: settings.speed.unwrap_or(CPU_SPEED_FACTOR),
+        rom: program.rom,
+    }
+}
+
+fn run_terminal(glyphs: Glyphs, keymap: Keymap, is_muted: bool, session: Session) -> Result<(), String> {
+    let display = TerminalDisplay::new(glyphs, session.palettes.current().clone())?;
+    let keypad = TerminalKeypad::new(keymap);
+    let bell = TerminalBell::new(is_muted);
+
+    let mut system = System::new(display, keypad, bell, session.processor, session.palettes, session.screenshots, session.recorder)
+        .with_speed(session.speed)
+        .with_fast_forward(session.fast_forward)
+        .with_program_watcher(session.watcher);
     system.run_loop();
 
     Ok(())
 }
+
+fn scaled_window_size(scale: u32) -> (u32, u32) {
+    ((CHIP8_WIDTH as u32) * scale, (CHIP8_HEIGHT as u32) * scale)
+}
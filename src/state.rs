@@ -0,0 +1,49 @@
+use std::fs;
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+use super::constants::APP_DIR;
+
+const STATE_FILE: &str = "state.toml";
+
+/// Settings remembered between runs of the interpreter.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct State {
+    pub window_size: Option<(u32, u32)>,
+}
+
+impl State {
+    /// Load state saved by the previous run. Missing or unreadable state is not an
+    /// error, the interpreter just starts with defaults.
+    pub fn load() -> Self {
+        let state = state_path()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|contents| toml::from_str(&contents)
+                .map_err(|e| warn!("Cannot parse saved state: {}", e))
+                .ok())
+            .unwrap_or_default();
+
+        debug!("Loaded state: {:?}", state);
+
+        state
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = state_path().ok_or("Cannot find config directory")?;
+        let contents = toml::to_string(self).map_err(|e| e.to_string())?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, contents).map_err(|e| e.to_string())?;
+
+        debug!("Saved state: path={:?}, state={:?}", path, self);
+
+        Ok(())
+    }
+}
+
+fn state_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join(APP_DIR).join(STATE_FILE))
+}
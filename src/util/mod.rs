@@ -1 +1,2 @@
 pub mod test;
+pub mod text;
@@ -0,0 +1,39 @@
+// Size of characters of the SDL2_gfx built-in font, and the space between lines.
+pub const FONT_SIZE: i32 = 8;
+pub const LINE_HEIGHT: i32 = 12;
+
+/// Break text into lines of at most the number of characters, between words where
+/// possible. Line breaks in the text are kept.
+pub fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+
+        for word in paragraph.split_whitespace() {
+            let mut word = word;
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            // Words longer than a line are cut.
+            while word.chars().count() > width {
+                let split = word.char_indices().nth(width).map_or(word.len(), |(index, _)| index);
+                lines.push(word[..split].to_owned());
+                word = &word[split..];
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+#[path = "./text_test.rs"]
+mod text_test;
@@ -0,0 +1,14 @@
+#![cfg(test)]
+use speculate::speculate;
+
+use super::*;
+
+speculate! {
+    describe "wrap_text" {
+        test "break lines between words and cut long words" {
+            assert_eq!(wrap_text("Pong for two players", 10), ["Pong for", "two", "players"]);
+            assert_eq!(wrap_text("abcdefghijkl", 5), ["abcde", "fghij", "kl"]);
+            assert_eq!(wrap_text("Left: Q\nRight: P", 20), ["Left: Q", "Right: P"]);
+        }
+    }
+}